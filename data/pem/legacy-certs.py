@@ -0,0 +1,78 @@
+#!/usr/bin/env python3
+"""Creates X.509 v1 and v2 certificates, which OpenSSL no longer issues.
+
+The certificates are self-signed with `ed25519-private.pem`. The v2 certificate has issuer and
+subject unique identifiers.
+"""
+import base64
+import subprocess
+import tempfile
+
+
+def tlv(tag, content):
+    if len(content) < 0x80:
+        length = bytes([len(content)])
+    else:
+        encoded = len(content).to_bytes((len(content).bit_length() + 7) // 8, "big")
+        length = bytes([0x80 | len(encoded)]) + encoded
+    return bytes([tag]) + length + content
+
+
+def seq(*elements):
+    return tlv(0x30, b"".join(elements))
+
+
+def integer(value):
+    return tlv(0x02, value.to_bytes(value.bit_length() // 8 + 1, "big"))
+
+
+def name(common_name):
+    # Name ::= SEQUENCE OF SET OF AttributeTypeAndValue, with commonName (2.5.4.3)
+    atv = seq(tlv(0x06, bytes([0x55, 0x04, 0x03])), tlv(0x0C, common_name.encode()))
+    return seq(tlv(0x31, atv))
+
+
+def certificate(version, common_name, unique_ids):
+    ed25519 = seq(tlv(0x06, bytes([0x2B, 0x65, 0x70])))
+    spki = subprocess.run(
+        ["openssl", "pkey", "-in", "ed25519-private.pem", "-pubout", "-outform", "DER"],
+        check=True,
+        capture_output=True,
+    ).stdout
+    fields = []
+    if version is not None:
+        fields.append(tlv(0xA0, integer(version)))
+    fields += [
+        integer(0x1234 + (version or 0)),
+        ed25519,
+        name(common_name),
+        seq(tlv(0x17, b"000101000000Z"), tlv(0x17, b"491231235959Z")),
+        name(common_name),
+        spki,
+    ]
+    if unique_ids:
+        # [1] and [2] IMPLICIT BIT STRING, with no unused bits
+        fields.append(tlv(0x81, b"\x00" + bytes.fromhex("0102030405")))
+        fields.append(tlv(0x82, b"\x00" + bytes.fromhex("a1a2a3a4a5a6")))
+    tbs = seq(*fields)
+    # Ed25519 signing in pkeyutl needs the whole input at once, which it only reads from files.
+    with tempfile.NamedTemporaryFile() as f:
+        f.write(tbs)
+        f.flush()
+        signature = subprocess.run(
+            ["openssl", "pkeyutl", "-sign", "-inkey", "ed25519-private.pem", "-rawin", "-in", f.name],
+            check=True,
+            capture_output=True,
+        ).stdout
+    return seq(tbs, ed25519, tlv(0x03, b"\x00" + signature))
+
+
+def write_pem(filename, der):
+    lines = base64.encodebytes(der).decode().replace("\n", "")
+    body = "\n".join(lines[i : i + 64] for i in range(0, len(lines), 64))
+    with open(filename, "w") as f:
+        f.write(f"-----BEGIN CERTIFICATE-----\n{body}\n-----END CERTIFICATE-----\n")
+
+
+write_pem("v1.crt", certificate(None, "Legacy v1 Device", False))
+write_pem("v2.crt", certificate(1, "Legacy v2 Device", True))
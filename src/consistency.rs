@@ -0,0 +1,214 @@
+//! Consistency checks of RSA private keys.
+//!
+//! RSA private keys store the CRT components of [RFC 8017](https://datatracker.ietf.org/doc/html/rfc8017#section-3.2)
+//! along with the values from which they are derived, so a key written by a buggy exporter may
+//! disagree with itself and only fail once it is used. Each check is identified by a short name
+//! and either passes or fails.
+use num_bigint::BigUint;
+use num_integer::Integer;
+use serde::Serialize;
+
+#[derive(Debug, PartialEq, Eq, Serialize)]
+pub struct Check {
+    pub check: &'static str,
+    pub passed: bool,
+}
+
+impl Check {
+    pub(crate) fn new(check: &'static str, passed: bool) -> Self {
+        Self { check, passed }
+    }
+}
+
+/// Bases of the Miller-Rabin test. The test is deterministic below 3.3 * 10^24 and a random
+/// composite passes it with negligible probability, which suffices to detect corrupted primes.
+const BASES: [u32; 12] = [2, 3, 5, 7, 11, 13, 17, 19, 23, 29, 31, 37];
+
+/// Returns whether `n` is probably a prime.
+fn is_probable_prime(n: &BigUint) -> bool {
+    let one = BigUint::from(1u8);
+    if *n <= one {
+        return false;
+    }
+    for base in BASES {
+        let base = BigUint::from(base);
+        if *n == base {
+            return true;
+        }
+        if (n % &base).bits() == 0 {
+            return false;
+        }
+    }
+    let n_1 = n - &one;
+    // n is odd, so n - 1 is nonzero and even.
+    let s = n_1.trailing_zeros().unwrap();
+    let d = &n_1 >> s;
+    BASES.iter().all(|&base| {
+        let mut x = BigUint::from(base).modpow(&d, n);
+        if x == one || x == n_1 {
+            return true;
+        }
+        for _ in 1..s {
+            x = &x * &x % n;
+            if x == n_1 {
+                return true;
+            }
+        }
+        false
+    })
+}
+
+/// The components of an RSA private key.
+pub(crate) struct RsaComponents {
+    pub(crate) n: BigUint,
+    pub(crate) e: BigUint,
+    pub(crate) d: BigUint,
+    /// prime1, prime2 and the other primes of multi-prime keys.
+    pub(crate) primes: Vec<BigUint>,
+    /// d mod (r_i - 1) for each prime r_i, or empty if the format does not store them.
+    pub(crate) exponents: Vec<BigUint>,
+    /// The coefficient of each prime but the first: q^-1 mod p for prime2, and
+    /// (r_1 * ... * r_(i-1))^-1 mod r_i for the other primes.
+    pub(crate) coefficients: Vec<BigUint>,
+}
+
+impl RsaComponents {
+    pub(crate) fn check(&self) -> Vec<Check> {
+        let primes = self.primes.len() >= 2
+            && self.primes.iter().all(is_probable_prime)
+            && self
+                .primes
+                .iter()
+                .enumerate()
+                .all(|(i, r)| !self.primes[..i].contains(r));
+        let mut checks = vec![
+            Check::new("modulus", self.primes.iter().product::<BigUint>() == self.n),
+            Check::new("primes", primes),
+            Check::new("private-exponent", primes && self.private_exponent()),
+        ];
+        if !self.exponents.is_empty() {
+            checks.push(Check::new("crt-exponents", self.crt_exponents()));
+        }
+        checks.push(Check::new("crt-coefficients", self.crt_coefficients()));
+        checks.push(Check::new("key-pair", self.key_pair()));
+        checks
+    }
+
+    /// e * d = 1 mod lcm(r_1 - 1, ..., r_u - 1), which requires every prime to be at least 2.
+    fn private_exponent(&self) -> bool {
+        let one = BigUint::from(1u8);
+        let lambda = self
+            .primes
+            .iter()
+            .fold(one.clone(), |lambda, r| lambda.lcm(&(r - 1u8)));
+        &self.e * &self.d % lambda == one
+    }
+
+    fn crt_exponents(&self) -> bool {
+        let one = BigUint::from(1u8);
+        self.exponents.len() == self.primes.len()
+            && self
+                .primes
+                .iter()
+                .zip(&self.exponents)
+                .all(|(r, exponent)| *r > one && &self.d % (r - 1u8) == *exponent)
+    }
+
+    fn crt_coefficients(&self) -> bool {
+        let one = BigUint::from(1u8);
+        if self.primes.len() < 2 || self.coefficients.len() != self.primes.len() - 1 {
+            return false;
+        }
+        let (p, q) = (&self.primes[0], &self.primes[1]);
+        if *p <= one || &self.coefficients[0] * q % p != one {
+            return false;
+        }
+        let mut product = p * q;
+        for (r, coefficient) in self.primes[2..].iter().zip(&self.coefficients[1..]) {
+            if *r <= one || coefficient * &product % r != one {
+                return false;
+            }
+            product *= r;
+        }
+        true
+    }
+
+    /// Decrypting with d what was encrypted with e yields the original message, which holds
+    /// whether or not the primes are intact.
+    fn key_pair(&self) -> bool {
+        let message = BigUint::from(2u8);
+        self.n > message && message.modpow(&self.e, &self.n).modpow(&self.d, &self.n) == message
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn components(values: &[u32]) -> Vec<BigUint> {
+        values.iter().map(|&value| value.into()).collect()
+    }
+
+    fn failed(key: &RsaComponents) -> Vec<&'static str> {
+        key.check()
+            .into_iter()
+            .filter(|check| !check.passed)
+            .map(|check| check.check)
+            .collect()
+    }
+
+    #[test]
+    fn check_test() {
+        // p = 61, q = 53, e = 17 and d = 2753
+        let mut key = RsaComponents {
+            n: 3233u32.into(),
+            e: 17u32.into(),
+            d: 2753u32.into(),
+            primes: components(&[61, 53]),
+            exponents: components(&[53, 49]),
+            coefficients: components(&[38]),
+        };
+        assert_eq!(key.check().len(), 6);
+        assert!(failed(&key).is_empty());
+        // p^-1 mod q instead of q^-1 mod p
+        key.coefficients = components(&[20]);
+        assert_eq!(failed(&key), ["crt-coefficients"]);
+        key.coefficients = components(&[38]);
+        key.d = 2754u32.into();
+        assert_eq!(
+            failed(&key),
+            ["private-exponent", "crt-exponents", "key-pair"]
+        );
+    }
+
+    #[test]
+    fn check_test_multi_prime() {
+        // r_3 = 59, whose coefficient is (61 * 53)^-1 mod 59 = 54
+        let key = RsaComponents {
+            n: (61u32 * 53 * 59).into(),
+            e: 17u32.into(),
+            d: 6653u32.into(),
+            primes: components(&[61, 53, 59]),
+            exponents: components(&[53, 49, 41]),
+            coefficients: components(&[38, 54]),
+        };
+        assert!(failed(&key).is_empty());
+        let key = RsaComponents {
+            primes: components(&[61, 53, 57]),
+            n: (61u32 * 53 * 57).into(),
+            ..key
+        };
+        assert!(failed(&key).contains(&"primes"));
+    }
+
+    #[test]
+    fn is_probable_prime_test() {
+        for prime in [2u32, 3, 37, 41, 65537, 2147483647] {
+            assert!(is_probable_prime(&prime.into()), "{}", prime);
+        }
+        // 3215031751 is a strong pseudoprime to the bases 2, 3, 5 and 7.
+        for composite in [0u32, 1, 4, 1369, 3215031751] {
+            assert!(!is_probable_prime(&composite.into()), "{}", composite);
+        }
+    }
+}
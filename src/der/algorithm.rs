@@ -0,0 +1,394 @@
+#![allow(non_snake_case)]
+use asn1_rs::{
+    oid, Any, Class, DerSequence, Error as Asn1Error, Integer, OctetString, Oid, OptTaggedExplicit,
+    Tag,
+};
+use oid_registry::OidRegistry;
+use serde::{Serialize, Serializer};
+
+use crate::der::encrypted::{self, GcmParams, Pbes2Params, Pbkdf2Params, ScryptParams};
+use crate::der::object::Object;
+use crate::der::{self, Raw};
+use crate::error::{Error, Result};
+use crate::fingerprint;
+use crate::int::DisplayedIntInner;
+use crate::span::{Defaulted, Span, Spanned};
+
+const OID_MGF1: Oid<'static> = oid!(1.2.840 .113549 .1 .1 .8);
+pub(crate) const OID_RSAES_OAEP: Oid<'static> = oid!(1.2.840 .113549 .1 .1 .7);
+const OID_P_SPECIFIED: Oid<'static> = oid!(1.2.840 .113549 .1 .1 .9);
+
+// RFC 2459
+// https://datatracker.ietf.org/doc/html/rfc2459#section-4.1.1.2
+#[derive(DerSequence, Debug)]
+pub(crate) struct AlgorithmIdentifierAsn1<'a> {
+    pub(crate) algorithm: Oid<'a>,
+    pub(crate) parameter: Option<Any<'a>>,
+}
+
+impl AlgorithmIdentifierAsn1<'_> {
+    pub(crate) fn to(&self, registry: &OidRegistry) -> Object {
+        let algo = &self.algorithm;
+        (algo, registry.get(algo)).into()
+    }
+}
+
+impl Raw<'_, AlgorithmIdentifierAsn1<'_>> {
+    /// Decodes the algorithm and its parameters, which are located in `base`.
+    pub(crate) fn decode(
+        &self,
+        registry: &OidRegistry,
+        base: &[u8],
+    ) -> Result<AlgorithmIdentifier> {
+        Ok(AlgorithmIdentifier {
+            algorithm: self.value.to(registry),
+            parameters: Parameters::decode(&self.value, registry, base)?,
+            span: Some(self.span(base)),
+        })
+    }
+}
+
+/// An algorithm and its parameters. DEFAULT algorithms have no span.
+#[derive(Serialize)]
+pub struct AlgorithmIdentifier {
+    pub algorithm: Object,
+    pub parameters: Parameters,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub span: Option<Span>,
+}
+
+impl AlgorithmIdentifier {
+    pub(crate) fn default(algorithm: Oid, parameters: Parameters, registry: &OidRegistry) -> Self {
+        AlgorithmIdentifier {
+            algorithm: (&algorithm, registry.get(&algorithm)).into(),
+            parameters,
+            span: None,
+        }
+    }
+
+    /// sha1Identifier of RFC 4055 Section 2.1
+    fn sha1(registry: &OidRegistry) -> Self {
+        Self::default(
+            oid_registry::OID_HASH_SHA1,
+            Parameters::Null(None),
+            registry,
+        )
+    }
+
+    /// mgf1SHA1Identifier of RFC 4055 Section 2.2
+    fn mgf1_sha1(registry: &OidRegistry) -> Self {
+        let sha1 = Parameters::Algorithm(Box::new(Self::sha1(registry)));
+        Self::default(OID_MGF1, sha1, registry)
+    }
+
+    /// pSpecifiedEmptyIdentifier of RFC 4055 Section 4.1
+    fn p_specified_empty(registry: &OidRegistry) -> Self {
+        let label = Parameters::Label(Defaulted::new(label(&[]), None));
+        Self::default(OID_P_SPECIFIED, label, registry)
+    }
+}
+
+/// Parameters of an [`AlgorithmIdentifier`], decoded for the algorithms that are known to have
+/// them.
+pub enum Parameters {
+    /// The parameters are absent, as opposed to NULL.
+    Absent,
+    /// NULL, e.g. for PKCS #1 algorithms.
+    Null(Option<Span>),
+    /// An OID, e.g. the named curve of id-ecPublicKey (RFC 5480 Section 2.1.1).
+    Object(Spanned<Object>),
+    /// The hash algorithm of MGF1 (RFC 8017 Appendix B.2.1).
+    Algorithm(Box<AlgorithmIdentifier>),
+    Pss(Box<RsassaPssParams>),
+    Oaep(Box<RsaesOaepParams>),
+    /// The label of pSpecified (RFC 8017 Appendix A.2.1).
+    Label(Defaulted<String>),
+    Pbes2(Box<Pbes2Params>),
+    Pbkdf2(Box<Pbkdf2Params>),
+    Scrypt(Box<ScryptParams>),
+    Gcm(Box<GcmParams>),
+    /// The IV of a cipher in CBC mode (RFC 8018 Appendix B.2).
+    Iv(Spanned<String>),
+    /// Parameters of unknown algorithms.
+    Other(Spanned<String>),
+}
+
+impl Serialize for Parameters {
+    fn serialize<S>(&self, serializer: S) -> std::result::Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        match self {
+            Parameters::Absent => serializer.serialize_str("absent"),
+            Parameters::Null(span) => Defaulted::new("NULL", *span).serialize(serializer),
+            Parameters::Object(value) => value.serialize(serializer),
+            Parameters::Algorithm(value) => value.serialize(serializer),
+            Parameters::Pss(value) => value.serialize(serializer),
+            Parameters::Oaep(value) => value.serialize(serializer),
+            Parameters::Label(value) => value.serialize(serializer),
+            Parameters::Pbes2(value) => value.serialize(serializer),
+            Parameters::Pbkdf2(value) => value.serialize(serializer),
+            Parameters::Scrypt(value) => value.serialize(serializer),
+            Parameters::Gcm(value) => value.serialize(serializer),
+            Parameters::Iv(value) => value.serialize(serializer),
+            Parameters::Other(value) => value.serialize(serializer),
+        }
+    }
+}
+
+impl Parameters {
+    fn decode(
+        value: &AlgorithmIdentifierAsn1,
+        registry: &OidRegistry,
+        base: &[u8],
+    ) -> Result<Self> {
+        let Some(param) = &value.parameter else {
+            return Ok(Parameters::Absent);
+        };
+        let span = der::any_span(base, param);
+        let err = Error::asn1("parameters", span);
+        let algorithm = &value.algorithm;
+        if param.class() == Class::Universal && param.tag() == Tag::Null {
+            return Ok(Parameters::Null(Some(span)));
+        }
+        if param.class() == Class::Universal && param.tag() == Tag::Oid {
+            let oid = Oid::try_from(param.clone()).map_err(err)?;
+            let object = (&oid, registry.get(&oid)).into();
+            return Ok(Parameters::Object(Spanned::new(object, span)));
+        }
+        if *algorithm == oid_registry::OID_PKCS1_RSASSAPSS {
+            let params = RsassaPssParamsAsn1::try_from(param.clone()).map_err(err)?;
+            return Ok(Parameters::Pss(Box::new(RsassaPssParams::decode(
+                params, registry, base, span,
+            )?)));
+        }
+        if *algorithm == OID_RSAES_OAEP {
+            let params = RsaesOaepParamsAsn1::try_from(param.clone()).map_err(err)?;
+            return Ok(Parameters::Oaep(Box::new(RsaesOaepParams::decode(
+                params, registry, base, span,
+            )?)));
+        }
+        if *algorithm == OID_MGF1 {
+            let hash = Raw {
+                der: &base[span.start..span.end],
+                value: AlgorithmIdentifierAsn1::try_from(param.clone()).map_err(err)?,
+            };
+            return Ok(Parameters::Algorithm(Box::new(
+                hash.decode(registry, base)?,
+            )));
+        }
+        if *algorithm == OID_P_SPECIFIED {
+            let value = OctetString::try_from(param.clone()).map_err(err)?;
+            return Ok(Parameters::Label(Defaulted::new(
+                label(value.as_cow()),
+                Some(span),
+            )));
+        }
+        if let Some(params) = encrypted::decode_parameters(algorithm, param, registry, base, span)?
+        {
+            return Ok(params);
+        }
+        Ok(Parameters::Other(Spanned::new(
+            format!("(parameters: {} bytes)", span.len()),
+            span,
+        )))
+    }
+}
+
+fn label(bytes: &[u8]) -> String {
+    if bytes.is_empty() {
+        "(empty)".to_string()
+    } else {
+        fingerprint::colon_hex(bytes, false)
+    }
+}
+
+/// Decodes an optional AlgorithmIdentifier of RSASSA-PSS-params or RSAES-OAEP-params, which takes
+/// `default` if it is absent.
+fn algorithm_or(
+    value: Option<Raw<AlgorithmIdentifierAsn1>>,
+    registry: &OidRegistry,
+    base: &[u8],
+    default: fn(&OidRegistry) -> AlgorithmIdentifier,
+) -> Result<AlgorithmIdentifier> {
+    match value {
+        Some(value) => value.decode(registry, base),
+        None => Ok(default(registry)),
+    }
+}
+
+/// Decodes an optional INTEGER, which is `default` if it is absent.
+pub(crate) fn integer_or(
+    value: Option<Integer>,
+    base: &[u8],
+    default: u32,
+) -> Defaulted<DisplayedIntInner> {
+    match value {
+        Some(value) => Defaulted::new(value.as_bigint().into(), Some(der::int_span(base, &value))),
+        None => Defaulted::new(default.into(), None),
+    }
+}
+
+// RFC 4055 Section 3.1
+// https://datatracker.ietf.org/doc/html/rfc4055#section-3.1
+#[derive(DerSequence)]
+struct RsassaPssParamsAsn1<'a> {
+    hashAlgorithm: OptTaggedExplicit<Raw<'a, AlgorithmIdentifierAsn1<'a>>, Asn1Error, 0>,
+    maskGenAlgorithm: OptTaggedExplicit<Raw<'a, AlgorithmIdentifierAsn1<'a>>, Asn1Error, 1>,
+    saltLength: OptTaggedExplicit<Integer<'a>, Asn1Error, 2>,
+    trailerField: OptTaggedExplicit<Integer<'a>, Asn1Error, 3>,
+}
+
+/// RSASSA-PSS-params, with the DEFAULT values of absent fields.
+#[derive(Serialize)]
+pub struct RsassaPssParams {
+    #[serde(rename = "hashAlgorithm")]
+    pub hash_algorithm: AlgorithmIdentifier,
+    #[serde(rename = "maskGenAlgorithm")]
+    pub mask_gen_algorithm: AlgorithmIdentifier,
+    #[serde(rename = "saltLength")]
+    pub salt_length: Defaulted<DisplayedIntInner>,
+    #[serde(rename = "trailerField")]
+    pub trailer_field: Defaulted<DisplayedIntInner>,
+    pub span: Span,
+}
+
+impl RsassaPssParams {
+    fn decode(
+        value: RsassaPssParamsAsn1,
+        registry: &OidRegistry,
+        base: &[u8],
+        span: Span,
+    ) -> Result<Self> {
+        Ok(RsassaPssParams {
+            hash_algorithm: algorithm_or(
+                value.hashAlgorithm.map(|x| x.into_inner()),
+                registry,
+                base,
+                AlgorithmIdentifier::sha1,
+            )?,
+            mask_gen_algorithm: algorithm_or(
+                value.maskGenAlgorithm.map(|x| x.into_inner()),
+                registry,
+                base,
+                AlgorithmIdentifier::mgf1_sha1,
+            )?,
+            salt_length: integer_or(value.saltLength.map(|x| x.into_inner()), base, 20),
+            trailer_field: integer_or(value.trailerField.map(|x| x.into_inner()), base, 1),
+            span,
+        })
+    }
+}
+
+// RFC 4055 Section 4.1
+// https://datatracker.ietf.org/doc/html/rfc4055#section-4.1
+#[derive(DerSequence)]
+struct RsaesOaepParamsAsn1<'a> {
+    hashAlgorithm: OptTaggedExplicit<Raw<'a, AlgorithmIdentifierAsn1<'a>>, Asn1Error, 0>,
+    maskGenAlgorithm: OptTaggedExplicit<Raw<'a, AlgorithmIdentifierAsn1<'a>>, Asn1Error, 1>,
+    pSourceAlgorithm: OptTaggedExplicit<Raw<'a, AlgorithmIdentifierAsn1<'a>>, Asn1Error, 2>,
+}
+
+/// RSAES-OAEP-params, with the DEFAULT values of absent fields.
+#[derive(Serialize)]
+pub struct RsaesOaepParams {
+    #[serde(rename = "hashAlgorithm")]
+    pub hash_algorithm: AlgorithmIdentifier,
+    #[serde(rename = "maskGenAlgorithm")]
+    pub mask_gen_algorithm: AlgorithmIdentifier,
+    #[serde(rename = "pSourceAlgorithm")]
+    pub p_source_algorithm: AlgorithmIdentifier,
+    pub span: Span,
+}
+
+impl RsaesOaepParams {
+    fn decode(
+        value: RsaesOaepParamsAsn1,
+        registry: &OidRegistry,
+        base: &[u8],
+        span: Span,
+    ) -> Result<Self> {
+        Ok(RsaesOaepParams {
+            hash_algorithm: algorithm_or(
+                value.hashAlgorithm.map(|x| x.into_inner()),
+                registry,
+                base,
+                AlgorithmIdentifier::sha1,
+            )?,
+            mask_gen_algorithm: algorithm_or(
+                value.maskGenAlgorithm.map(|x| x.into_inner()),
+                registry,
+                base,
+                AlgorithmIdentifier::mgf1_sha1,
+            )?,
+            p_source_algorithm: algorithm_or(
+                value.pSourceAlgorithm.map(|x| x.into_inner()),
+                registry,
+                base,
+                AlgorithmIdentifier::p_specified_empty,
+            )?,
+            span,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use asn1_rs::FromDer;
+
+    use super::*;
+    use crate::der::registry;
+
+    fn decode(der: &[u8]) -> serde_json::Value {
+        let (_, value) = Raw::<AlgorithmIdentifierAsn1>::from_der(der).unwrap();
+        serde_json::to_value(value.decode(&registry::get(), der).unwrap()).unwrap()
+    }
+
+    #[test]
+    fn null_and_absent_test() {
+        // sha256WithRSAEncryption, with and without NULL
+        let null = decode(&[
+            0x30, 0x0d, 0x06, 0x09, 0x2a, 0x86, 0x48, 0x86, 0xf7, 0x0d, 0x01, 0x01, 0x0b, 0x05,
+            0x00,
+        ]);
+        assert_eq!(
+            null["parameters"],
+            serde_json::json!({ "value": "NULL", "span": { "start": 13, "end": 15 } })
+        );
+        let absent = decode(&[
+            0x30, 0x0b, 0x06, 0x09, 0x2a, 0x86, 0x48, 0x86, 0xf7, 0x0d, 0x01, 0x01, 0x0b,
+        ]);
+        assert_eq!(absent["parameters"], "absent");
+    }
+
+    #[test]
+    fn oaep_test() {
+        // RSAES-OAEP with SHA-256 and the label "abc", and the default MGF1 with SHA-1
+        let value = decode(&[
+            0x30, 0x30, 0x06, 0x09, 0x2a, 0x86, 0x48, 0x86, 0xf7, 0x0d, 0x01, 0x01, 0x07, 0x30,
+            0x23, 0xa0, 0x0d, 0x30, 0x0b, 0x06, 0x09, 0x60, 0x86, 0x48, 0x01, 0x65, 0x03, 0x04,
+            0x02, 0x01, 0xa2, 0x12, 0x30, 0x10, 0x06, 0x09, 0x2a, 0x86, 0x48, 0x86, 0xf7, 0x0d,
+            0x01, 0x01, 0x09, 0x04, 0x03, 0x61, 0x62, 0x63,
+        ]);
+        let params = &value["parameters"];
+        assert_eq!(
+            params["hashAlgorithm"]["algorithm"],
+            "sha256 (2.16.840.1.101.3.4.2.1)"
+        );
+        assert_eq!(params["hashAlgorithm"]["parameters"], "absent");
+        assert_eq!(
+            params["maskGenAlgorithm"],
+            serde_json::json!({
+                "algorithm": "mgf1 (1.2.840.113549.1.1.8)",
+                "parameters": {
+                    "algorithm": "id-SHA1 (1.3.14.3.2.26)",
+                    "parameters": { "value": "NULL" },
+                },
+            })
+        );
+        assert_eq!(
+            params["pSourceAlgorithm"]["parameters"],
+            serde_json::json!({ "value": "61:62:63", "span": { "start": 45, "end": 50 } })
+        );
+    }
+}
@@ -0,0 +1,125 @@
+//! Attributes of certification requests and private keys.
+#![allow(non_snake_case)]
+use asn1_rs::{oid, Any, Class, DerSequence, FromDer, OctetString, Oid, Set};
+use oid_registry::OidRegistry;
+use serde::Serialize;
+
+use crate::der::extension::{self, Extension};
+use crate::der::name;
+use crate::der::object::Object;
+use crate::der::{self, Raw};
+use crate::error::{Error, Result};
+use crate::fingerprint;
+use crate::secret::Secret;
+use crate::span::{Span, Spanned};
+
+// https://datatracker.ietf.org/doc/html/rfc2986#section-4
+#[derive(DerSequence)]
+struct AttributeAsn1<'a> {
+    r#type: Oid<'a>,
+    values: Set<'a>,
+}
+
+/// Decoded value of an attribute. The attributes of PKCS #9
+/// ([RFC 2985](https://datatracker.ietf.org/doc/html/rfc2985)) that are used in requests and
+/// private keys are decoded, and the others are displayed with their length.
+#[derive(Serialize)]
+#[serde(untagged)]
+pub enum AttributeValue {
+    /// extensionRequest, the extensions to be included in the certificate.
+    Extensions(Vec<Extension>),
+    /// challengePassword, which is displayed according to the secret policy.
+    Secret(Secret),
+    /// unstructuredName and friendlyName
+    String(Spanned<String>),
+    /// localKeyID
+    Bytes(Spanned<String>),
+    /// Summary of a value that is not decoded.
+    Unknown(Spanned<String>),
+}
+
+#[derive(Serialize)]
+pub struct Attribute {
+    #[serde(rename = "type")]
+    pub ty: Spanned<Object>,
+    pub values: Vec<AttributeValue>,
+    pub span: Span,
+}
+
+impl Attribute {
+    fn from(raw: Raw<AttributeAsn1>, registry: &OidRegistry, base: &[u8]) -> Result<Self> {
+        let ty = &raw.value.r#type;
+        let span = raw.span(base);
+        let mut values = vec![];
+        let mut rem: &[u8] = &raw.value.values.content;
+        while !rem.is_empty() {
+            let (next, value) =
+                Any::from_der(rem).map_err(|e| Error::asn1("values", span)(e.into()))?;
+            values.push(attribute_value(ty, &value, registry, base)?);
+            rem = next;
+        }
+        Ok(Attribute {
+            ty: Spanned::new(
+                (ty, registry.get(ty)).into(),
+                der::tlv_span(base, ty.as_bytes()),
+            ),
+            values,
+            span,
+        })
+    }
+}
+
+fn attribute_value(
+    ty: &Oid,
+    value: &Any,
+    registry: &OidRegistry,
+    base: &[u8],
+) -> Result<AttributeValue> {
+    let span = der::any_span(base, value);
+    let string = name::decode_string(value);
+    Ok(if *ty == oid!(1.2.840 .113549 .1 .9 .14) {
+        AttributeValue::Extensions(extension::parse_extensions(value, registry, base)?)
+    } else if let (true, Some(password)) = (*ty == oid!(1.2.840 .113549 .1 .9 .7), &string) {
+        AttributeValue::Secret(Secret::text(password, span))
+    } else if let (true, Some(name)) = (
+        *ty == oid!(1.2.840 .113549 .1 .9 .2) || *ty == oid!(1.2.840 .113549 .1 .9 .20),
+        string,
+    ) {
+        AttributeValue::String(Spanned::new(name, span))
+    } else if let (true, Ok(id)) = (
+        *ty == oid!(1.2.840 .113549 .1 .9 .21),
+        OctetString::try_from(value.clone()),
+    ) {
+        AttributeValue::Bytes(Spanned::new(
+            fingerprint::colon_hex(id.as_cow(), false),
+            span,
+        ))
+    } else {
+        AttributeValue::Unknown(Spanned::new(format!("({} bytes)", value.data.len()), span))
+    })
+}
+
+/// Decodes the attributes of requests and private keys, which are `[0] IMPLICIT SET OF
+/// Attribute`.
+pub(crate) fn parse_attributes(
+    value: &Any,
+    registry: &OidRegistry,
+    base: &[u8],
+) -> Result<Vec<Attribute>> {
+    let span = der::any_span(base, value);
+    let err = |e: asn1_rs::Error| Error::asn1("attributes", span)(e);
+    if value.class() != Class::ContextSpecific || value.tag().0 != 0 {
+        return Err(err(asn1_rs::Error::unexpected_tag(
+            Some(asn1_rs::Tag(0)),
+            value.tag(),
+        )));
+    }
+    let mut rem = value.data;
+    let mut attributes = vec![];
+    while !rem.is_empty() {
+        let (next, attribute) = Raw::<AttributeAsn1>::from_der(rem).map_err(|e| err(e.into()))?;
+        attributes.push(Attribute::from(attribute, registry, base)?);
+        rem = next;
+    }
+    Ok(attributes)
+}
@@ -1,72 +1,118 @@
 #![allow(non_snake_case)]
 
 use asn1_rs::{
-    Any, BitString, DerSequence, Error as Asn1Error, FromDer, Integer, OptTaggedExplicit, Sequence,
-    TaggedExplicit,
+    Any, BitString, DerSequence, Error as Asn1Error, Integer, OptTaggedExplicit, OptTaggedImplicit,
+    Sequence,
 };
 use oid_registry::OidRegistry;
 use serde::Serialize;
 
-use crate::der::object::Object;
-use crate::der::pubkey::{AlgorithmIdentifierAsn1, PublicKey, SubjectPublicKeyInfoAsn1};
-use crate::der::registry;
-use crate::error::{Error, Result};
+use crate::der::algorithm::{AlgorithmIdentifier, AlgorithmIdentifierAsn1};
+use crate::der::extension::{self, Extension};
+use crate::der::name::Name;
+use crate::der::pubkey::{PublicKey, SubjectPublicKeyInfoAsn1};
+use crate::der::validity::{Validity, ValidityAsn1};
+use crate::der::{self, registry, Raw};
+use crate::error::Result;
+use crate::fingerprint::{self, Thumbprint};
 use crate::int::DisplayedInt;
-use crate::span::Span;
+use crate::span::{Defaulted, Span, Spanned};
 use crate::string::BitStr;
+use crate::verify::{Key, Signed, Verification};
 
 #[derive(DerSequence)]
 struct TBSCertificateAsn1<'a> {
-    version: TaggedExplicit<Integer<'a>, Asn1Error, 0>,
+    version: OptTaggedExplicit<Integer<'a>, Asn1Error, 0>,
     serialNumber: Integer<'a>,
-    signature: AlgorithmIdentifierAsn1<'a>,
-    #[allow(unused)]
+    signature: Raw<'a, AlgorithmIdentifierAsn1<'a>>,
     issuer: Sequence<'a>,
-    #[allow(unused)]
-    validity: Any<'a>,
-    #[allow(unused)]
+    validity: Raw<'a, ValidityAsn1<'a>>,
     subject: Sequence<'a>,
-    subjectPublicKeyInfo: SubjectPublicKeyInfoAsn1<'a>,
-    // issuerUniqueID: OptTaggedImplicit<BitString<'a>, 1>,
-    // subjectUniqueID: Option<Any<'a>>, //TaggedValue<BitString<'a>, Implicit, 0b10 /* ContextSpecific */, 2>,
-    #[allow(unused)]
+    subjectPublicKeyInfo: Raw<'a, SubjectPublicKeyInfoAsn1<'a>>,
+    issuerUniqueID: OptTaggedImplicit<BitString<'a>, Asn1Error, 1>,
+    subjectUniqueID: OptTaggedImplicit<BitString<'a>, Asn1Error, 2>,
     extensions: OptTaggedExplicit<Any<'a>, Asn1Error, 3>,
 }
 
+/// Returns the effective version of a certificate. The field is absent in most v1 certificates,
+/// which have the DEFAULT v1.
+fn version(value: Option<&Integer>, base: &[u8]) -> Defaulted<String> {
+    let Some(value) = value else {
+        return Defaulted::new("v1".to_string(), None);
+    };
+    let name = match value.as_u32() {
+        Ok(n @ 0..=2) => format!("v{}", n + 1),
+        _ => format!("unknown (0x{})", value.as_bigint().to_str_radix(16)),
+    };
+    Defaulted::new(name, Some(der::int_span(base, value)))
+}
+
+/// Formats a unique identifier (RFC 5280 Section 4.1.2.8), spanning its whole TLV.
+fn unique_id(value: Option<BitString>, base: &[u8]) -> Option<Spanned<String>> {
+    value.map(|id| {
+        let span = der::bit_string_span(base, &id.data);
+        Spanned::new(fingerprint::colon_hex(&id.data, false), span)
+    })
+}
+
 #[derive(Serialize)]
 pub struct TBSCertificate {
-    pub version: DisplayedInt,
+    pub version: Defaulted<String>,
     #[serde(rename = "serialNumber")]
     pub serial_number: DisplayedInt,
-    pub signature: Object,
-    issuer: (),
-    validity: (),
-    subject: (),
+    pub signature: AlgorithmIdentifier,
+    pub issuer: Name,
+    #[serde(rename = "issuerRFC4514")]
+    pub issuer_rfc4514: String,
+    pub validity: Validity,
+    pub subject: Name,
+    #[serde(rename = "subjectRFC4514")]
+    pub subject_rfc4514: String,
     #[serde(rename = "subjectPublicKeyInfo")]
     pub subject_pki: PublicKey,
-    issuer_uid: (),
+    #[serde(rename = "issuerUniqueID", skip_serializing_if = "Option::is_none")]
+    pub issuer_unique_id: Option<Spanned<String>>,
+    #[serde(rename = "subjectUniqueID", skip_serializing_if = "Option::is_none")]
+    pub subject_unique_id: Option<Spanned<String>>,
+    pub extensions: Option<Vec<Extension>>,
+    pub span: Span,
 }
 
 impl TBSCertificate {
-    fn try_from(value: TBSCertificateAsn1, registry: &OidRegistry) -> Result<Self> {
-        eprintln!("{:?}", value.extensions);
+    fn try_from(raw: Raw<TBSCertificateAsn1>, registry: &OidRegistry, base: &[u8]) -> Result<Self> {
+        let span = raw.span(base);
+        let value = raw.value;
+        let issuer = Name::from(&value.issuer, registry, base, "issuer")?;
+        let subject = Name::from(&value.subject, registry, base, "subject")?;
         Ok(TBSCertificate {
-            version: DisplayedInt::new(value.version.into_inner().as_bigint(), Span::new(0, 0)), // TODO span
-            serial_number: DisplayedInt::new(value.serialNumber.as_bigint(), Span::new(0, 0)), // TODO span
-            signature: value.signature.to(registry),
-            issuer: (),
-            validity: (),
-            subject: (),
-            subject_pki: value.subjectPublicKeyInfo.to(registry)?,
-            issuer_uid: (),
+            version: version(value.version.as_ref().map(|v| v.as_ref()), base),
+            serial_number: der::displayed_int(base, &value.serialNumber),
+            signature: value.signature.decode(registry, base)?,
+            issuer_rfc4514: issuer.rfc4514.clone(),
+            issuer,
+            validity: Validity::from(&value.validity, base)?,
+            subject_rfc4514: subject.rfc4514.clone(),
+            subject,
+            subject_pki: value.subjectPublicKeyInfo.value.to(
+                registry,
+                base,
+                value.subjectPublicKeyInfo.der,
+            )?,
+            issuer_unique_id: unique_id(value.issuerUniqueID.map(|id| id.into_inner()), base),
+            subject_unique_id: unique_id(value.subjectUniqueID.map(|id| id.into_inner()), base),
+            extensions: value
+                .extensions
+                .map(|x| extension::parse_extensions(&x.into_inner(), registry, base))
+                .transpose()?,
+            span,
         })
     }
 }
 
 #[derive(DerSequence)]
 struct CertificateAsn1<'a> {
-    tbsCertificate: TBSCertificateAsn1<'a>,
-    signatureAlgorithm: AlgorithmIdentifierAsn1<'a>,
+    tbsCertificate: Raw<'a, TBSCertificateAsn1<'a>>,
+    signatureAlgorithm: Raw<'a, AlgorithmIdentifierAsn1<'a>>,
     signatureValue: BitString<'a>,
 }
 
@@ -75,26 +121,63 @@ pub struct Certificate {
     #[serde(rename = "tbsCertificate")]
     pub tbs_certificate: TBSCertificate,
     #[serde(rename = "signatureAlgorithm")]
-    pub signature_algorithm: Object,
+    pub signature_algorithm: AlgorithmIdentifier,
     #[serde(rename = "signatureValue")]
-    pub signature_value: BitStr,
+    pub signature_value: Spanned<BitStr>,
+    /// Hashes of the whole certificate.
+    pub thumbprint: Thumbprint,
+    /// Outcome of the verification of the signature, if it was verified.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub verification: Option<Verification>,
+    #[serde(skip)]
+    signed: Signed,
+    /// Whether the issuer is the subject. Such certificates are verified with their own key,
+    /// unless they are signed with an algorithm of another type of key.
+    #[serde(skip)]
+    pub self_issued: bool,
+    pub span: Span,
 }
 
 impl Certificate {
-    fn try_from(value: CertificateAsn1, registry: &OidRegistry) -> Result<Self> {
+    fn try_from(value: CertificateAsn1, registry: &OidRegistry, base: &[u8]) -> Result<Self> {
+        let tbs = value.tbsCertificate;
+        let signed = Signed {
+            message: tbs.der.to_vec(),
+            algorithm: value.signatureAlgorithm.der.to_vec(),
+            signature: value.signatureValue.data.to_vec(),
+        };
+        let self_issued = tbs.value.issuer.content == tbs.value.subject.content;
+        let signature_span = der::bit_string_span(base, &value.signatureValue.data);
         Ok(Self {
-            tbs_certificate: TBSCertificate::try_from(value.tbsCertificate, registry)?,
-            signature_algorithm: value.signatureAlgorithm.to(registry),
-            signature_value: value.signatureValue.into(),
+            tbs_certificate: TBSCertificate::try_from(tbs, registry, base)?,
+            signature_algorithm: value.signatureAlgorithm.decode(registry, base)?,
+            signature_value: Spanned::new(value.signatureValue.into(), signature_span),
+            thumbprint: Thumbprint::new(base),
+            verification: None,
+            signed,
+            self_issued,
+            span: Span::new(0, base.len()),
         })
     }
+
+    /// Verifies the signature with `key`, the key of the issuer named `issuer`, and records
+    /// the outcome.
+    pub fn verify(&mut self, key: &Key, issuer: &str) {
+        self.verification = Some(self.signed.verify(key, issuer));
+    }
 }
 
 pub fn parse(content: &[u8]) -> Result<Certificate> {
     let registry = registry::get();
-    let (content, value) = CertificateAsn1::from_der(content).map_err(asn1_rs::Error::from)?;
-    if !content.is_empty() {
-        return Err(Error::ParseError);
+    let value: CertificateAsn1 = der::parse_der(content, "Certificate")?;
+    let mut cert = Certificate::try_from(value, &registry, content)?;
+    if cert.self_issued {
+        cert.verification = match Key::from_spki(&cert.tbs_certificate.subject_pki.der) {
+            // Not self-signed, but signed by another issuer of the same name.
+            Some(key) if !cert.signed.algorithm_matches(&key) => None,
+            Some(key) => Some(cert.signed.verify(&key, "self")),
+            None => Some(Verification::unsupported_key("self")),
+        };
     }
-    Certificate::try_from(value, &registry)
+    Ok(cert)
 }
@@ -1,91 +1,68 @@
 #![allow(non_snake_case)]
-use asn1_rs::{Any, BitString, DerSequence, FromDer, Integer, Oid, Sequence, Set};
+use asn1_rs::{Any, BitString, DerSequence, Integer, Sequence};
 use oid_registry::OidRegistry;
 use serde::Serialize;
 
-use crate::der::object::Object;
-use crate::der::pubkey::{AlgorithmIdentifierAsn1, PublicKey, SubjectPublicKeyInfoAsn1};
-use crate::der::registry;
-use crate::error::{Error, Result};
+use crate::der::algorithm::{AlgorithmIdentifier, AlgorithmIdentifierAsn1};
+use crate::der::attribute::{self, Attribute};
+use crate::der::name::Name;
+use crate::der::pubkey::{PublicKey, SubjectPublicKeyInfoAsn1};
+use crate::der::{self, registry, Raw};
+use crate::error::Result;
 use crate::int::DisplayedInt;
-use crate::span::Span;
+use crate::span::{Span, Spanned};
 use crate::string::BitStr;
-
-// https://www.itu.int/ITU-T/formal-language/itu-t/x/x501/2012/InformationFramework.html#InformationFramework.AttributeTypeAndValue
-#[derive(DerSequence)]
-#[allow(unused)]
-struct AttributeTypeAndValueAsn1<'a> {
-    r#type: Oid<'a>,
-    value: Any<'a>,
-}
-
-#[derive(Serialize)]
-pub struct AttributeTypeAndValue {
-    #[serde(rename = "type")]
-    pub ty: Object,
-    pub value: (), // TODO
-}
-
-impl AttributeTypeAndValue {
-    fn from(x: AttributeTypeAndValueAsn1, registry: &OidRegistry) -> Self {
-        let entry = registry.get(&x.r#type);
-        eprintln!("value = {:?}", x.value);
-        Self {
-            ty: (&x.r#type, entry).into(),
-            value: (),
-        }
-    }
-}
+use crate::verify::{Key, Signed, Verification};
 
 // https://datatracker.ietf.org/doc/html/rfc2986#section-4
 #[derive(DerSequence)]
 struct CertificationRequestInfoAsn1<'a> {
     version: Integer<'a>,
     subject: Sequence<'a>,
-    subjectPKInfo: SubjectPublicKeyInfoAsn1<'a>,
-    #[allow(unused)]
+    subjectPKInfo: Raw<'a, SubjectPublicKeyInfoAsn1<'a>>,
     attributes: Any<'a>,
 }
 
 #[derive(Serialize)]
 pub struct CertificationRequestInfo {
     version: DisplayedInt,
-    subject: Vec<Vec<AttributeTypeAndValue>>,
+    subject: Name,
+    #[serde(rename = "subjectRFC4514")]
+    pub subject_rfc4514: String,
     #[serde(rename = "subjectPKInfo")]
     pub subject_pk_info: PublicKey,
+    pub attributes: Vec<Attribute>,
+    pub span: Span,
 }
 
-impl CertificationRequestInfoAsn1<'_> {
-    fn to(self, registry: &OidRegistry) -> Result<CertificationRequestInfo> {
-        CertificationRequestInfo::from(self, registry)
-    }
-}
 impl CertificationRequestInfo {
-    fn from(value: CertificationRequestInfoAsn1, registry: &OidRegistry) -> Result<Self> {
-        let subject: Vec<Vec<AttributeTypeAndValue>> = value
-            .subject
-            .der_iter()
-            .map(|x| {
-                let set: Set = x?;
-                let val: Vec<_> = set
-                    .der_iter()
-                    .map(|x| Ok(AttributeTypeAndValue::from(x?, registry)))
-                    .collect::<asn1_rs::Result<Vec<_>>>()?;
-                Ok(val)
-            })
-            .collect::<asn1_rs::Result<Vec<_>>>()?;
+    fn from(
+        raw: Raw<CertificationRequestInfoAsn1>,
+        registry: &OidRegistry,
+        base: &[u8],
+    ) -> Result<Self> {
+        let span = raw.span(base);
+        let value = raw.value;
+        let subject = Name::from(&value.subject, registry, base, "subject")?;
         Ok(CertificationRequestInfo {
-            version: DisplayedInt::new(value.version.as_bigint(), Span::new(0, 0)), // TODO span
+            version: der::displayed_int(base, &value.version),
+            subject_rfc4514: subject.rfc4514.clone(),
             subject,
-            subject_pk_info: value.subjectPKInfo.to(registry)?,
+            subject_pk_info: value.subjectPKInfo.value.to(
+                registry,
+                base,
+                value.subjectPKInfo.der,
+            )?,
+            attributes: attribute::parse_attributes(&value.attributes, registry, base)?,
+            span,
         })
     }
 }
 // https://datatracker.ietf.org/doc/html/rfc2986#section-4
 #[derive(DerSequence)]
 pub struct CertificationRequestAsn1<'a> {
-    certificationRequestInfo: CertificationRequestInfoAsn1<'a>,
-    signatureAlgorithm: AlgorithmIdentifierAsn1<'a>,
+    certificationRequestInfo: Raw<'a, CertificationRequestInfoAsn1<'a>>,
+    signatureAlgorithm: Raw<'a, AlgorithmIdentifierAsn1<'a>>,
     signature: BitString<'a>,
 }
 
@@ -94,27 +71,39 @@ pub struct CertificationRequest {
     #[serde(rename = "certificationRequestInfo")]
     pub certification_request_info: CertificationRequestInfo,
     #[serde(rename = "signatureAlgorithm")]
-    pub signature_algorithm: Object,
-    pub signature: BitStr,
+    pub signature_algorithm: AlgorithmIdentifier,
+    pub signature: Spanned<BitStr>,
+    /// Outcome of the verification of the signature with the key of the request, which proves
+    /// that the requester possesses the private key.
+    pub verification: Verification,
+    pub span: Span,
 }
 
 impl CertificationRequestAsn1<'_> {
-    fn to(self, registry: &OidRegistry) -> Result<CertificationRequest> {
+    fn to(self, registry: &OidRegistry, base: &[u8]) -> Result<CertificationRequest> {
+        let signature_span = der::bit_string_span(base, &self.signature.data);
+        let signed = Signed {
+            message: self.certificationRequestInfo.der.to_vec(),
+            algorithm: self.signatureAlgorithm.der.to_vec(),
+            signature: self.signature.data.to_vec(),
+        };
+        let info = CertificationRequestInfo::from(self.certificationRequestInfo, registry, base)?;
+        let verification = match Key::from_spki(&info.subject_pk_info.der) {
+            Some(key) => signed.verify(&key, "self"),
+            None => Verification::unsupported_key("self"),
+        };
         Ok(CertificationRequest {
-            certification_request_info: self.certificationRequestInfo.to(registry)?,
-            signature_algorithm: self.signatureAlgorithm.to(registry),
-            signature: self.signature.clone().into(),
+            certification_request_info: info,
+            signature_algorithm: self.signatureAlgorithm.decode(registry, base)?,
+            signature: Spanned::new(self.signature.into(), signature_span),
+            verification,
+            span: Span::new(0, base.len()),
         })
     }
 }
 
 pub fn parse_csr(content: &[u8]) -> Result<CertificationRequest> {
     let registry = registry::get();
-    let (content, value) =
-        CertificationRequestAsn1::from_der(content).map_err(asn1_rs::Error::from)?;
-    if !content.is_empty() {
-        return Err(Error::ParseError);
-    }
-    eprintln!("{:?}", value.certificationRequestInfo.attributes);
-    value.to(&registry)
+    let value: CertificationRequestAsn1 = der::parse_der(content, "CertificationRequest")?;
+    value.to(&registry, content)
 }
@@ -0,0 +1,142 @@
+//! DSA keys of [RFC 3279 Section 2.3.2](https://datatracker.ietf.org/doc/html/rfc3279#section-2.3.2)
+//! and the traditional OpenSSL `DSA PRIVATE KEY`.
+use asn1_rs::{oid, Any, DerSequence, Integer, Oid};
+use serde::Serialize;
+
+use crate::der;
+use crate::error::{Error, Result};
+use crate::int::DisplayedInt;
+use crate::secret::Secret;
+use crate::span::Span;
+
+/// id-dsa
+pub(crate) const OID_DSA: Oid<'static> = oid!(1.2.840 .10040 .4 .1);
+
+// https://datatracker.ietf.org/doc/html/rfc3279#section-2.3.2
+#[derive(DerSequence)]
+struct DssParmsAsn1<'a> {
+    p: Integer<'a>,
+    q: Integer<'a>,
+    g: Integer<'a>,
+}
+
+#[derive(Serialize)]
+pub struct DssParms {
+    pub p: DisplayedInt,
+    pub q: DisplayedInt,
+    pub g: DisplayedInt,
+}
+
+/// Decodes the parameters of the AlgorithmIdentifier of a DSA key, which are absent if they are
+/// inherited from the issuer (RFC 3279 Section 2.3.2).
+pub(crate) fn parameters(value: Option<&Any>, base: &[u8]) -> Result<Option<DssParms>> {
+    let Some(value) = value else {
+        return Ok(None);
+    };
+    let span = der::any_span(base, value);
+    let parms = DssParmsAsn1::try_from(value.clone()).map_err(Error::asn1("parameters", span))?;
+    Ok(Some(DssParms {
+        p: der::displayed_int(base, &parms.p),
+        q: der::displayed_int(base, &parms.q),
+        g: der::displayed_int(base, &parms.g),
+    }))
+}
+
+#[derive(Serialize)]
+pub struct DsaPublicKey {
+    #[serde(flatten)]
+    pub parameters: Option<DssParms>,
+    pub pub_key: DisplayedInt,
+    pub span: Span,
+}
+
+#[derive(Serialize)]
+pub struct DsaPrivateKey {
+    /// Version of the traditional format, which PKCS #8 keys do not have.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub version: Option<DisplayedInt>,
+    #[serde(flatten)]
+    pub parameters: Option<DssParms>,
+    /// The public value, which PKCS #8 keys do not have.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub pub_key: Option<DisplayedInt>,
+    pub priv_key: Secret,
+    pub span: Span,
+}
+
+fn secret(base: &[u8], value: &Integer) -> Secret {
+    Secret::integer(&value.as_bigint(), der::int_span(base, value))
+}
+
+pub mod privkey {
+    use super::*;
+
+    // The traditional format of OpenSSL, as written by `i2d_DSAPrivateKey`.
+    #[derive(DerSequence)]
+    struct DsaPrivateKeyAsn1<'a> {
+        version: Integer<'a>,
+        p: Integer<'a>,
+        q: Integer<'a>,
+        g: Integer<'a>,
+        pub_key: Integer<'a>,
+        priv_key: Integer<'a>,
+    }
+
+    /// Parses a traditional `DSA PRIVATE KEY`.
+    pub fn parse(content: &[u8]) -> Result<DsaPrivateKey> {
+        let key: DsaPrivateKeyAsn1 = der::parse_der(content, "DSAPrivateKey")?;
+        if key.version.as_i32() != Ok(0) {
+            return Err(Error::InvalidInputError {
+                field: "version",
+                span: der::int_span(content, &key.version),
+            });
+        }
+        Ok(DsaPrivateKey {
+            version: Some(der::displayed_int(content, &key.version)),
+            parameters: Some(DssParms {
+                p: der::displayed_int(content, &key.p),
+                q: der::displayed_int(content, &key.q),
+                g: der::displayed_int(content, &key.g),
+            }),
+            pub_key: Some(der::displayed_int(content, &key.pub_key)),
+            priv_key: secret(content, &key.priv_key),
+            span: Span::new(0, content.len()),
+        })
+    }
+
+    /// Parses the private value `content` of a PKCS #8 key, a subslice of `base` to which the
+    /// spans refer.
+    pub(crate) fn parse_in(
+        content: &[u8],
+        base: &[u8],
+        parameters: Option<DssParms>,
+    ) -> Result<DsaPrivateKey> {
+        let x: Integer = der::parse_der(content, "DSAPrivateKey")?;
+        Ok(DsaPrivateKey {
+            version: None,
+            parameters,
+            pub_key: None,
+            priv_key: secret(base, &x),
+            span: der::span_in(base, content),
+        })
+    }
+}
+
+pub mod pubkey {
+    use super::*;
+
+    /// Parses the public value `content` of a SubjectPublicKeyInfo, a subslice of `base` to
+    /// which the spans refer.
+    pub(crate) fn parse_in(
+        content: &[u8],
+        base: &[u8],
+        parameters: Option<DssParms>,
+    ) -> Result<DsaPublicKey> {
+        let y: Integer = der::parse_der(content, "DSAPublicKey")?;
+        Ok(DsaPublicKey {
+            parameters,
+            pub_key: der::displayed_int(base, &y),
+            span: der::span_in(base, content),
+        })
+    }
+}
@@ -0,0 +1,208 @@
+//! Elliptic-curve keys of [SEC 1](https://www.secg.org/sec1-v2.pdf) on named curves, as used with
+//! ECDSA and ECDH.
+#![allow(non_snake_case)]
+use asn1_rs::{
+    oid, Any, BitString, DerSequence, Error as Asn1Error, Integer, OctetString, Oid,
+    OptTaggedExplicit,
+};
+use num_bigint::{BigInt, Sign};
+use oid_registry::OidRegistry;
+use serde::Serialize;
+
+use crate::der::object::Object;
+use crate::der::{self, registry, Raw};
+use crate::error::{Error, Result};
+use crate::int::DisplayedInt;
+use crate::secret::Secret;
+use crate::span::{Span, Spanned};
+
+/// Named curves whose keys are decoded, with the size of their coordinates in bytes.
+const CURVES: [(Oid<'static>, &str, usize); 4] = [
+    (oid!(1.2.840 .10045 .3 .1 .7), "P-256", 32),
+    (oid!(1.3.132 .0 .34), "P-384", 48),
+    (oid!(1.3.132 .0 .35), "P-521", 66),
+    (oid!(1.3.132 .0 .10), "secp256k1", 32),
+];
+
+/// Returns the name and the coordinate size of the named curve `oid`.
+fn curve(oid: Option<&Oid>) -> Option<(&'static str, usize)> {
+    let oid = oid?;
+    CURVES
+        .iter()
+        .find(|(known, _, _)| known == oid)
+        .map(|&(_, name, size)| (name, size))
+}
+
+/// Returns the named curve in the ECParameters `value`. Explicit parameters and implicitCA,
+/// which RFC 5480 Section 2.1.1 forbids, are not supported.
+pub(crate) fn named_curve<'a>(value: Option<&Any<'a>>) -> Option<Oid<'a>> {
+    Oid::try_from(value?).ok()
+}
+
+#[derive(Serialize)]
+pub struct EcPublicKey {
+    /// Name of the curve, or `unknown` if it is not supported.
+    pub curve: &'static str,
+    /// Encoding of the point (SEC 1 Section 2.3.3): `compressed`, `uncompressed` or `hybrid`.
+    pub format: &'static str,
+    pub x: DisplayedInt,
+    /// The y coordinate, which compressed points omit.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub y: Option<DisplayedInt>,
+    pub span: Span,
+}
+
+#[derive(Serialize)]
+pub struct EcPrivateKey {
+    pub version: DisplayedInt,
+    /// Name of the curve, from the parameters of the key or from the algorithm of the PKCS #8
+    /// structure that contains it.
+    pub curve: &'static str,
+    #[serde(rename = "privateKey")]
+    pub private_key: Secret,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub parameters: Option<Spanned<Object>>,
+    #[serde(rename = "publicKey", skip_serializing_if = "Option::is_none")]
+    pub public_key: Option<EcPublicKey>,
+    pub span: Span,
+}
+
+pub mod pubkey {
+    use super::*;
+
+    /// Parses the ECPoint `content` on the curve `curve`. `content` is a subslice of `base` to
+    /// which the spans refer, but errors are located in `content`.
+    pub(crate) fn parse_in(
+        content: &[u8],
+        base: &[u8],
+        curve: Option<&Oid>,
+    ) -> Result<EcPublicKey> {
+        let err = || Error::InvalidInputError {
+            field: "ECPoint",
+            span: Span::new(0, content.len()),
+        };
+        let (name, size) = super::curve(curve).unwrap_or(("unknown", 0));
+        let (format, coordinates) = match content.first() {
+            Some(2 | 3) => ("compressed", 1),
+            Some(4) => ("uncompressed", 2),
+            Some(6 | 7) => ("hybrid", 2),
+            _ => return Err(err()),
+        };
+        let len = content.len() - 1;
+        // The size of the coordinates of unknown curves is derived from the encoding.
+        let size = if size == 0 { len / coordinates } else { size };
+        if size == 0 || len != size * coordinates {
+            return Err(err());
+        }
+        let coordinate = |i: usize| {
+            let bytes = &content[1 + i * size..1 + (i + 1) * size];
+            DisplayedInt::new(
+                BigInt::from_bytes_be(Sign::Plus, bytes),
+                der::span_in(base, bytes),
+            )
+        };
+        Ok(EcPublicKey {
+            curve: name,
+            format,
+            x: coordinate(0),
+            y: (coordinates == 2).then(|| coordinate(1)),
+            span: der::span_in(base, content),
+        })
+    }
+}
+
+pub mod privkey {
+    use super::*;
+
+    // RFC 5915
+    // https://datatracker.ietf.org/doc/html/rfc5915#section-3
+    #[derive(DerSequence)]
+    struct EcPrivateKeyAsn1<'a> {
+        version: Integer<'a>,
+        privateKey: OctetString<'a>,
+        parameters: OptTaggedExplicit<Raw<'a, Any<'a>>, Asn1Error, 0>,
+        publicKey: OptTaggedExplicit<BitString<'a>, Asn1Error, 1>,
+    }
+
+    pub fn parse(content: &[u8]) -> Result<EcPrivateKey> {
+        parse_in(content, content, &registry::get(), None)
+    }
+
+    /// Returns the private scalar of the ECPrivateKey `content`.
+    pub(crate) fn scalar(content: &[u8]) -> Option<Vec<u8>> {
+        let key: EcPrivateKeyAsn1 = der::parse_der(content, "ECPrivateKey").ok()?;
+        Some(key.privateKey.as_cow().to_vec())
+    }
+
+    /// Parses `content`, a subslice of `base` to which the spans refer. `curve` is the curve
+    /// named by the algorithm of the enclosing PKCS #8 structure, if any, which the parameters
+    /// of the key may omit.
+    pub(crate) fn parse_in(
+        content: &[u8],
+        base: &[u8],
+        registry: &OidRegistry,
+        curve: Option<&Oid>,
+    ) -> Result<EcPrivateKey> {
+        let key: EcPrivateKeyAsn1 = der::parse_der(content, "ECPrivateKey")?;
+        if key.version.as_i32() != Ok(1) {
+            return Err(Error::InvalidInputError {
+                field: "version",
+                span: der::int_span(content, &key.version),
+            });
+        }
+        let parameters = key.parameters.map(|x| x.into_inner());
+        let named = match &parameters {
+            Some(raw) => Some(
+                named_curve(Some(&raw.value)).ok_or(Error::InvalidInputError {
+                    field: "parameters",
+                    span: raw.span(content),
+                })?,
+            ),
+            None => None,
+        };
+        let curve = named.as_ref().or(curve);
+        let scalar = key.privateKey.as_cow();
+        let public_key = key
+            .publicKey
+            .map(|point| {
+                let point = &point.as_ref().data;
+                pubkey::parse_in(point, base, curve)
+                    .map_err(|e| e.shift(der::span_in(content, point).start))
+            })
+            .transpose()?;
+        Ok(EcPrivateKey {
+            version: der::displayed_int(base, &key.version),
+            curve: super::curve(curve).map_or("unknown", |(name, _)| name),
+            private_key: Secret::bytes(scalar, der::tlv_span(base, scalar)),
+            parameters: parameters.zip(named).map(|(raw, oid)| {
+                let entry = registry.get(&oid);
+                Spanned::new((&oid, entry).into(), raw.span(base))
+            }),
+            public_key,
+            span: der::span_in(base, content),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn point_test() {
+        let p256 = oid!(1.2.840 .10045 .3 .1 .7);
+        let mut point = vec![0x03];
+        point.extend_from_slice(&[0xab; 32]);
+        let key = pubkey::parse_in(&point, &point, Some(&p256)).unwrap();
+        assert_eq!((key.curve, key.format), ("P-256", "compressed"));
+        assert_eq!(key.x.span, Span::new(1, 33));
+        assert!(key.y.is_none());
+        // A coordinate is missing.
+        point[0] = 0x04;
+        assert!(pubkey::parse_in(&point, &point, Some(&p256)).is_err());
+        // Unknown curves are decoded if the encoding is consistent.
+        let key = pubkey::parse_in(&point, &point, None).unwrap();
+        assert_eq!((key.curve, key.format), ("unknown", "uncompressed"));
+        assert_eq!(key.y.unwrap().span, Span::new(17, 33));
+    }
+}
@@ -1,28 +1,36 @@
 use serde::Serialize;
 
+use crate::der;
 use crate::error::Result;
-use crate::int::PrivateInt;
+use crate::secret::Secret;
+use crate::span::Spanned;
 use crate::string::BitStr;
 
 #[derive(Serialize)]
 pub struct EdPrivateKey {
-    pub scalar: PrivateInt,
+    pub scalar: Secret,
 }
 
 #[derive(Serialize)]
 pub struct EdPublicKey {
-    pub point: BitStr,
+    pub point: Spanned<BitStr>,
 }
 
 pub mod privkey {
-    use asn1_rs::{FromDer, Integer, OctetString};
+    use asn1_rs::OctetString;
 
     use super::*;
 
     pub fn parse(content: &[u8]) -> Result<EdPrivateKey> {
-        let (_, inner) = OctetString::from_der(content).map_err(asn1_rs::Error::from)?;
+        parse_in(content, content)
+    }
+
+    /// Parses `content`, a subslice of `base` to which the spans refer.
+    pub(crate) fn parse_in(content: &[u8], base: &[u8]) -> Result<EdPrivateKey> {
+        let inner: OctetString = der::parse_der(content, "CurvePrivateKey")?;
+        let scalar = inner.as_cow();
         Ok(EdPrivateKey {
-            scalar: Integer::new(inner.as_cow()).into(),
+            scalar: Secret::bytes(scalar, der::tlv_span(base, scalar)),
         })
     }
 }
@@ -33,9 +41,14 @@ pub mod pubkey {
     use super::*;
 
     pub fn parse(content: &[u8]) -> Result<EdPublicKey> {
+        Ok(parse_in(content, content))
+    }
+
+    /// Parses `content`, a subslice of `base` to which the spans refer.
+    pub(crate) fn parse_in(content: &[u8], base: &[u8]) -> EdPublicKey {
         let point = BitString::new(0, content);
-        Ok(EdPublicKey {
-            point: point.into(),
-        })
+        EdPublicKey {
+            point: Spanned::new(point.into(), der::span_in(base, content)),
+        }
     }
 }
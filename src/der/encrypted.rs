@@ -0,0 +1,754 @@
+//! Encrypted private keys (EncryptedPrivateKeyInfo of
+//! [RFC 5958 Section 3](https://datatracker.ietf.org/doc/html/rfc5958#section-3)) and their
+//! decryption with PBES2 ([RFC 8018 Section 6.2](https://datatracker.ietf.org/doc/html/rfc8018#section-6.2)).
+//!
+//! The [`legacy`] module handles the older encryption of traditional PEM keys.
+//!
+//! Keys are only decrypted if a passphrase is given in [`crate::document::ParseOptions`].
+//! Without a passphrase, only the encryption scheme is decoded.
+#![allow(non_snake_case)]
+use aes::{Aes128, Aes192, Aes256};
+use aes_gcm::aead::consts::{U12, U16};
+use aes_gcm::aead::generic_array::GenericArray;
+use aes_gcm::aead::{Aead, AeadCore, KeyInit};
+use aes_gcm::AesGcm;
+use asn1_rs::{oid, Any, DerSequence, FromDer, Integer, OctetString, Oid, Tag};
+use cbc::cipher::block_padding::Pkcs7;
+use cbc::cipher::{BlockDecryptMut, KeyIvInit};
+use des::TdesEde3;
+use oid_registry::OidRegistry;
+use serde::Serialize;
+use sha1::Sha1;
+use sha2::{Sha224, Sha256, Sha384, Sha512};
+
+use crate::der::algorithm::{self, AlgorithmIdentifier, AlgorithmIdentifierAsn1, Parameters};
+use crate::der::{self, registry, Raw};
+use crate::error::{Error, Result};
+use crate::fingerprint;
+use crate::int::{DisplayedInt, DisplayedIntInner};
+use crate::span::{Defaulted, Span, Spanned};
+
+const OID_PBES2: Oid<'static> = oid!(1.2.840 .113549 .1 .5 .13);
+const OID_PBKDF2: Oid<'static> = oid!(1.2.840 .113549 .1 .5 .12);
+// RFC 7914 Section 7
+const OID_SCRYPT: Oid<'static> = oid!(1.3.6 .1 .4 .1 .11591 .4 .11);
+const OID_HMAC_WITH_SHA1: Oid<'static> = oid!(1.2.840 .113549 .2 .7);
+
+/// The largest iteration count of PBKDF2 that is accepted, so that a crafted key cannot keep the
+/// process busy for hours. OpenSSL uses 2048 iterations by default.
+const MAX_PBKDF2_ITERATIONS: u32 = 10_000_000;
+/// The largest amount of memory that scrypt may use, in bytes. This is the default limit of
+/// OpenSSL, which also rejects keys whose parameters exceed it.
+const MAX_SCRYPT_MEMORY: u64 = 32 * 1024 * 1024;
+
+/// Hash functions of the PRFs of PBKDF2 (RFC 8018 Appendix B.1).
+#[derive(Clone, Copy)]
+enum Prf {
+    Sha1,
+    Sha224,
+    Sha256,
+    Sha384,
+    Sha512,
+}
+
+const PRFS: [(Oid<'static>, Prf); 5] = [
+    (OID_HMAC_WITH_SHA1, Prf::Sha1),
+    (oid!(1.2.840 .113549 .2 .8), Prf::Sha224),
+    (oid!(1.2.840 .113549 .2 .9), Prf::Sha256),
+    (oid!(1.2.840 .113549 .2 .10), Prf::Sha384),
+    (oid!(1.2.840 .113549 .2 .11), Prf::Sha512),
+];
+
+/// Encryption schemes of PBES2 (RFC 8018 Appendix B.2 and RFC 5084 Section 3.2).
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum Cipher {
+    Aes128Cbc,
+    Aes192Cbc,
+    Aes256Cbc,
+    Aes128Gcm,
+    Aes192Gcm,
+    Aes256Gcm,
+    DesEde3Cbc,
+}
+
+const CIPHERS: [(Oid<'static>, Cipher); 7] = [
+    (oid!(2.16.840 .1 .101 .3 .4 .1 .2), Cipher::Aes128Cbc),
+    (oid!(2.16.840 .1 .101 .3 .4 .1 .22), Cipher::Aes192Cbc),
+    (oid!(2.16.840 .1 .101 .3 .4 .1 .42), Cipher::Aes256Cbc),
+    (oid!(2.16.840 .1 .101 .3 .4 .1 .6), Cipher::Aes128Gcm),
+    (oid!(2.16.840 .1 .101 .3 .4 .1 .26), Cipher::Aes192Gcm),
+    (oid!(2.16.840 .1 .101 .3 .4 .1 .46), Cipher::Aes256Gcm),
+    (oid!(1.2.840 .113549 .3 .7), Cipher::DesEde3Cbc),
+];
+
+impl Cipher {
+    fn from_oid(oid: &Oid) -> Option<Self> {
+        CIPHERS
+            .iter()
+            .find(|(known, _)| known == oid)
+            .map(|&(_, cipher)| cipher)
+    }
+
+    fn key_len(self) -> usize {
+        match self {
+            Cipher::Aes128Cbc | Cipher::Aes128Gcm => 16,
+            Cipher::Aes192Cbc | Cipher::Aes192Gcm | Cipher::DesEde3Cbc => 24,
+            Cipher::Aes256Cbc | Cipher::Aes256Gcm => 32,
+        }
+    }
+
+    fn block_len(self) -> usize {
+        if self == Cipher::DesEde3Cbc {
+            8
+        } else {
+            16
+        }
+    }
+
+    /// Decrypts `data` with a cipher in CBC mode. See [`cbc_decrypt`].
+    fn decrypt_cbc(
+        self,
+        key: &[u8],
+        iv: &[u8],
+        data: &[u8],
+    ) -> Option<std::result::Result<Vec<u8>, ()>> {
+        match self {
+            Cipher::Aes128Cbc => cbc_decrypt::<cbc::Decryptor<Aes128>>(key, iv, data),
+            Cipher::Aes192Cbc => cbc_decrypt::<cbc::Decryptor<Aes192>>(key, iv, data),
+            Cipher::Aes256Cbc => cbc_decrypt::<cbc::Decryptor<Aes256>>(key, iv, data),
+            Cipher::DesEde3Cbc => cbc_decrypt::<cbc::Decryptor<TdesEde3>>(key, iv, data),
+            _ => None,
+        }
+    }
+
+    fn is_gcm(self) -> bool {
+        matches!(
+            self,
+            Cipher::Aes128Gcm | Cipher::Aes192Gcm | Cipher::Aes256Gcm
+        )
+    }
+}
+
+// https://datatracker.ietf.org/doc/html/rfc5958#section-3
+#[derive(DerSequence)]
+struct EncryptedPrivateKeyInfoAsn1<'a> {
+    encryptionAlgorithm: Raw<'a, AlgorithmIdentifierAsn1<'a>>,
+    encryptedData: OctetString<'a>,
+}
+
+// https://datatracker.ietf.org/doc/html/rfc8018#appendix-A.4
+#[derive(DerSequence)]
+struct Pbes2ParamsAsn1<'a> {
+    keyDerivationFunc: Raw<'a, AlgorithmIdentifierAsn1<'a>>,
+    encryptionScheme: Raw<'a, AlgorithmIdentifierAsn1<'a>>,
+}
+
+// https://datatracker.ietf.org/doc/html/rfc8018#appendix-A.2
+// The otherSource choice of salt is reserved, so only specified salts are supported.
+#[derive(DerSequence)]
+struct Pbkdf2ParamsAsn1<'a> {
+    salt: OctetString<'a>,
+    iterationCount: Integer<'a>,
+    keyLength: Option<Integer<'a>>,
+    prf: Option<Raw<'a, AlgorithmIdentifierAsn1<'a>>>,
+}
+
+// https://datatracker.ietf.org/doc/html/rfc7914#section-7.1
+#[derive(DerSequence)]
+struct ScryptParamsAsn1<'a> {
+    salt: OctetString<'a>,
+    costParameter: Integer<'a>,
+    blockSize: Integer<'a>,
+    parallelizationParameter: Integer<'a>,
+    keyLength: Option<Integer<'a>>,
+}
+
+// https://datatracker.ietf.org/doc/html/rfc5084#section-3.2
+#[derive(DerSequence)]
+struct GcmParametersAsn1<'a> {
+    nonce: OctetString<'a>,
+    icvLen: Option<Integer<'a>>,
+}
+
+/// PBES2-params
+#[derive(Serialize)]
+pub struct Pbes2Params {
+    #[serde(rename = "keyDerivationFunc")]
+    pub key_derivation_func: AlgorithmIdentifier,
+    #[serde(rename = "encryptionScheme")]
+    pub encryption_scheme: AlgorithmIdentifier,
+    pub span: Span,
+}
+
+/// PBKDF2-params, with the DEFAULT PRF if it is absent.
+#[derive(Serialize)]
+pub struct Pbkdf2Params {
+    pub salt: Spanned<String>,
+    #[serde(rename = "iterationCount")]
+    pub iteration_count: DisplayedInt,
+    #[serde(rename = "keyLength", skip_serializing_if = "Option::is_none")]
+    pub key_length: Option<DisplayedInt>,
+    pub prf: AlgorithmIdentifier,
+    pub span: Span,
+}
+
+/// scrypt-params
+#[derive(Serialize)]
+pub struct ScryptParams {
+    pub salt: Spanned<String>,
+    #[serde(rename = "costParameter")]
+    pub cost_parameter: DisplayedInt,
+    #[serde(rename = "blockSize")]
+    pub block_size: DisplayedInt,
+    #[serde(rename = "parallelizationParameter")]
+    pub parallelization_parameter: DisplayedInt,
+    #[serde(rename = "keyLength", skip_serializing_if = "Option::is_none")]
+    pub key_length: Option<DisplayedInt>,
+    pub span: Span,
+}
+
+/// GCMParameters, with the DEFAULT ICV length if it is absent.
+#[derive(Serialize)]
+pub struct GcmParams {
+    #[serde(rename = "aes-nonce")]
+    pub nonce: Spanned<String>,
+    #[serde(rename = "aes-ICVlen")]
+    pub icv_len: Defaulted<DisplayedIntInner>,
+    pub span: Span,
+}
+
+fn bytes(value: &OctetString, base: &[u8]) -> Spanned<String> {
+    let value = value.as_cow();
+    Spanned::new(
+        fingerprint::colon_hex(value, false),
+        der::tlv_span(base, value),
+    )
+}
+
+fn optional_int(value: &Option<Integer>, base: &[u8]) -> Option<DisplayedInt> {
+    value.as_ref().map(|value| der::displayed_int(base, value))
+}
+
+/// Decodes the parameters of the algorithms of PBES2, or returns `None` for other algorithms.
+pub(crate) fn decode_parameters(
+    algorithm: &Oid,
+    param: &Any,
+    registry: &OidRegistry,
+    base: &[u8],
+    span: Span,
+) -> Result<Option<Parameters>> {
+    let err = Error::asn1("parameters", span);
+    let parameters = if *algorithm == OID_PBES2 {
+        let value = Pbes2ParamsAsn1::try_from(param.clone()).map_err(err)?;
+        Parameters::Pbes2(Box::new(Pbes2Params {
+            key_derivation_func: value.keyDerivationFunc.decode(registry, base)?,
+            encryption_scheme: value.encryptionScheme.decode(registry, base)?,
+            span,
+        }))
+    } else if *algorithm == OID_PBKDF2 {
+        let value = Pbkdf2ParamsAsn1::try_from(param.clone()).map_err(err)?;
+        let prf = match value.prf {
+            Some(prf) => prf.decode(registry, base)?,
+            None => {
+                AlgorithmIdentifier::default(OID_HMAC_WITH_SHA1, Parameters::Null(None), registry)
+            }
+        };
+        Parameters::Pbkdf2(Box::new(Pbkdf2Params {
+            salt: bytes(&value.salt, base),
+            iteration_count: der::displayed_int(base, &value.iterationCount),
+            key_length: optional_int(&value.keyLength, base),
+            prf,
+            span,
+        }))
+    } else if *algorithm == OID_SCRYPT {
+        let value = ScryptParamsAsn1::try_from(param.clone()).map_err(err)?;
+        Parameters::Scrypt(Box::new(ScryptParams {
+            salt: bytes(&value.salt, base),
+            cost_parameter: der::displayed_int(base, &value.costParameter),
+            block_size: der::displayed_int(base, &value.blockSize),
+            parallelization_parameter: der::displayed_int(base, &value.parallelizationParameter),
+            key_length: optional_int(&value.keyLength, base),
+            span,
+        }))
+    } else if Cipher::from_oid(algorithm).is_some_and(Cipher::is_gcm) {
+        let value = GcmParametersAsn1::try_from(param.clone()).map_err(err)?;
+        Parameters::Gcm(Box::new(GcmParams {
+            nonce: bytes(&value.nonce, base),
+            icv_len: algorithm::integer_or(value.icvLen, base, 12),
+            span,
+        }))
+    } else if Cipher::from_oid(algorithm).is_some() {
+        // The IV of CBC modes
+        let value = OctetString::try_from(param.clone()).map_err(err)?;
+        Parameters::Iv(bytes(&value, base))
+    } else {
+        return Ok(None);
+    };
+    Ok(Some(parameters))
+}
+
+#[derive(Serialize)]
+pub struct EncryptedPrivateKey {
+    #[serde(rename = "encryptionAlgorithm")]
+    pub encryption_algorithm: AlgorithmIdentifier,
+    #[serde(rename = "encryptedData")]
+    pub encrypted_data: Spanned<String>,
+    pub span: Span,
+}
+
+/// Parses an EncryptedPrivateKeyInfo without decrypting it.
+pub fn parse(content: &[u8]) -> Result<EncryptedPrivateKey> {
+    let info: EncryptedPrivateKeyInfoAsn1 = der::parse_der(content, "EncryptedPrivateKeyInfo")?;
+    let data = info.encryptedData.as_cow();
+    Ok(EncryptedPrivateKey {
+        encryption_algorithm: info.encryptionAlgorithm.decode(&registry::get(), content)?,
+        encrypted_data: Spanned::new(
+            format!("({} bytes)", data.len()),
+            der::tlv_span(content, data),
+        ),
+        span: Span::new(0, content.len()),
+    })
+}
+
+/// Decrypts the EncryptedPrivateKeyInfo `content` with `passphrase` into a PrivateKeyInfo.
+///
+/// A wrong passphrase is reported as [`Error::WrongPassphrase`], and corrupted data as invalid
+/// input.
+pub fn decrypt(content: &[u8], passphrase: &[u8]) -> Result<Vec<u8>> {
+    let info: EncryptedPrivateKeyInfoAsn1 = der::parse_der(content, "EncryptedPrivateKeyInfo")?;
+    decrypt_info(&info, passphrase, content)
+}
+
+/// Decrypts the PrivateKeyInfo in `info`, which was parsed from `base`.
+fn decrypt_info(
+    info: &EncryptedPrivateKeyInfoAsn1,
+    passphrase: &[u8],
+    base: &[u8],
+) -> Result<Vec<u8>> {
+    let algorithm = &info.encryptionAlgorithm;
+    if algorithm.value.algorithm != OID_PBES2 {
+        return Err(Error::UnsupportedAlgorithm {
+            field: "encryptionAlgorithm",
+            span: algorithm.span(base),
+        });
+    }
+    let span = algorithm.span(base);
+    let params = algorithm
+        .value
+        .parameter
+        .clone()
+        .ok_or(Error::InvalidInputError {
+            field: "encryptionAlgorithm",
+            span,
+        })?;
+    let params = Pbes2ParamsAsn1::try_from(params).map_err(Error::asn1("parameters", span))?;
+    let scheme = &params.encryptionScheme;
+    let cipher = Cipher::from_oid(&scheme.value.algorithm).ok_or(Error::UnsupportedAlgorithm {
+        field: "encryptionScheme",
+        span: scheme.span(base),
+    })?;
+    let key = derive_key(
+        &params.keyDerivationFunc,
+        passphrase,
+        cipher.key_len(),
+        base,
+    )?;
+    let invalid_scheme = Error::InvalidInputError {
+        field: "encryptionScheme",
+        span: scheme.span(base),
+    };
+    let param = scheme.value.parameter.clone().ok_or(invalid_scheme)?;
+    let data = info.encryptedData.as_cow();
+    let invalid_data = || Error::InvalidInputError {
+        field: "encryptedData",
+        span: der::tlv_span(base, data),
+    };
+    let plaintext = if cipher.is_gcm() {
+        let gcm = GcmParametersAsn1::try_from(param)
+            .map_err(Error::asn1("parameters", scheme.span(base)))?;
+        let nonce = gcm.nonce.as_cow();
+        let icv_len = gcm.icvLen.map_or(Ok(12), |len| len.as_u32());
+        match (cipher, icv_len) {
+            (Cipher::Aes128Gcm, Ok(12)) => open::<AesGcm<Aes128, U12, U12>>(&key, nonce, data),
+            (Cipher::Aes128Gcm, Ok(16)) => open::<AesGcm<Aes128, U12, U16>>(&key, nonce, data),
+            (Cipher::Aes192Gcm, Ok(12)) => open::<AesGcm<Aes192, U12, U12>>(&key, nonce, data),
+            (Cipher::Aes192Gcm, Ok(16)) => open::<AesGcm<Aes192, U12, U16>>(&key, nonce, data),
+            (Cipher::Aes256Gcm, Ok(12)) => open::<AesGcm<Aes256, U12, U12>>(&key, nonce, data),
+            (Cipher::Aes256Gcm, Ok(16)) => open::<AesGcm<Aes256, U12, U16>>(&key, nonce, data),
+            _ => None,
+        }
+        .ok_or(Error::UnsupportedAlgorithm {
+            field: "encryptionScheme",
+            span: scheme.span(base),
+        })?
+    } else {
+        let iv =
+            OctetString::try_from(param).map_err(Error::asn1("parameters", scheme.span(base)))?;
+        if data.is_empty() || data.len() % cipher.block_len() != 0 {
+            return Err(invalid_data());
+        }
+        cipher
+            .decrypt_cbc(&key, iv.as_cow(), data)
+            .ok_or(Error::InvalidInputError {
+                field: "parameters",
+                span: scheme.span(base),
+            })?
+    };
+    check_plaintext(plaintext)
+}
+
+/// Checks that `plaintext`, which is `Err` if its padding or tag is wrong, is a DER SEQUENCE.
+///
+/// A wrong key yields a bad padding or a bad tag, which are reported as `WrongPassphrase`.
+/// If the padding is accidentally right, the plaintext is garbage rather than DER.
+fn check_plaintext(plaintext: std::result::Result<Vec<u8>, ()>) -> Result<Vec<u8>> {
+    let plaintext = plaintext.map_err(|()| Error::WrongPassphrase)?;
+    match Any::from_der(&plaintext) {
+        Ok((rem, any)) if rem.is_empty() && any.tag() == Tag::Sequence => Ok(plaintext),
+        _ => Err(Error::WrongPassphrase),
+    }
+}
+
+/// Derives a key of `len` bytes from `passphrase` with the key derivation function `kdf`.
+fn derive_key(
+    kdf: &Raw<AlgorithmIdentifierAsn1>,
+    passphrase: &[u8],
+    len: usize,
+    base: &[u8],
+) -> Result<Vec<u8>> {
+    let span = kdf.span(base);
+    let invalid = |field| Error::InvalidInputError { field, span };
+    let param = kdf
+        .value
+        .parameter
+        .clone()
+        .ok_or(invalid("keyDerivationFunc"))?;
+    let check_len = |key_length: &Option<Integer>| match key_length {
+        Some(value) if value.as_u64() != Ok(len as u64) => Err(invalid("keyLength")),
+        _ => Ok(()),
+    };
+    let mut key = vec![0; len];
+    if kdf.value.algorithm == OID_PBKDF2 {
+        let params = Pbkdf2ParamsAsn1::try_from(param).map_err(Error::asn1("parameters", span))?;
+        check_len(&params.keyLength)?;
+        let rounds = match params.iterationCount.as_u32() {
+            Ok(0) | Err(_) => return Err(invalid("iterationCount")),
+            Ok(rounds) if rounds > MAX_PBKDF2_ITERATIONS => {
+                return Err(Error::UnsupportedAlgorithm {
+                    field: "iterationCount",
+                    span: der::int_span(base, &params.iterationCount),
+                })
+            }
+            Ok(rounds) => rounds,
+        };
+        let prf = match &params.prf {
+            None => Prf::Sha1,
+            Some(prf) => PRFS
+                .iter()
+                .find(|(oid, _)| *oid == prf.value.algorithm)
+                .map(|&(_, prf)| prf)
+                .ok_or(Error::UnsupportedAlgorithm {
+                    field: "prf",
+                    span: prf.span(base),
+                })?,
+        };
+        let salt = params.salt.as_cow();
+        match prf {
+            Prf::Sha1 => pbkdf2::pbkdf2_hmac::<Sha1>(passphrase, salt, rounds, &mut key),
+            Prf::Sha224 => pbkdf2::pbkdf2_hmac::<Sha224>(passphrase, salt, rounds, &mut key),
+            Prf::Sha256 => pbkdf2::pbkdf2_hmac::<Sha256>(passphrase, salt, rounds, &mut key),
+            Prf::Sha384 => pbkdf2::pbkdf2_hmac::<Sha384>(passphrase, salt, rounds, &mut key),
+            Prf::Sha512 => pbkdf2::pbkdf2_hmac::<Sha512>(passphrase, salt, rounds, &mut key),
+        }
+    } else if kdf.value.algorithm == OID_SCRYPT {
+        let params = ScryptParamsAsn1::try_from(param).map_err(Error::asn1("parameters", span))?;
+        check_len(&params.keyLength)?;
+        // The cost parameter N must be a power of 2 (RFC 7914 Section 2).
+        let n = match params.costParameter.as_u64() {
+            Ok(n) if n > 1 && n.is_power_of_two() => n,
+            _ => return Err(invalid("costParameter")),
+        };
+        let r = params
+            .blockSize
+            .as_u32()
+            .map_err(|_| invalid("blockSize"))?;
+        let p = params
+            .parallelizationParameter
+            .as_u32()
+            .map_err(|_| invalid("parallelizationParameter"))?;
+        // scrypt needs 128 * r * N bytes for its vector V, and 128 * r * p bytes for its blocks.
+        let memory = (n + u64::from(p)).checked_mul(128 * u64::from(r));
+        if !memory.is_some_and(|memory| memory <= MAX_SCRYPT_MEMORY) {
+            return Err(Error::UnsupportedAlgorithm {
+                field: "costParameter",
+                span: der::int_span(base, &params.costParameter),
+            });
+        }
+        let log_n = n.trailing_zeros() as u8;
+        let cost = scrypt::Params::new(log_n, r, p, len).map_err(|_| invalid("parameters"))?;
+        scrypt::scrypt(passphrase, params.salt.as_cow(), &cost, &mut key)
+            .map_err(|_| invalid("keyLength"))?;
+    } else {
+        return Err(Error::UnsupportedAlgorithm {
+            field: "keyDerivationFunc",
+            span,
+        });
+    }
+    Ok(key)
+}
+
+/// Decrypts `data` in CBC mode with PKCS #7 padding. Returns `None` if the key or the IV have
+/// wrong lengths, and `Some(Err(()))` if the padding is wrong.
+fn cbc_decrypt<C: BlockDecryptMut + KeyIvInit>(
+    key: &[u8],
+    iv: &[u8],
+    data: &[u8],
+) -> Option<std::result::Result<Vec<u8>, ()>> {
+    let decryptor = C::new_from_slices(key, iv).ok()?;
+    Some(
+        decryptor
+            .decrypt_padded_vec_mut::<Pkcs7>(data)
+            .map_err(|_| ()),
+    )
+}
+
+/// Decrypts and authenticates `data` with an AEAD. Returns `None` if the key or the nonce have
+/// wrong lengths, and `Some(Err(()))` if the tag is wrong.
+fn open<A: Aead + AeadCore + KeyInit>(
+    key: &[u8],
+    nonce: &[u8],
+    data: &[u8],
+) -> Option<std::result::Result<Vec<u8>, ()>> {
+    if nonce.len() != GenericArray::<u8, A::NonceSize>::default().len() {
+        return None;
+    }
+    let aead = A::new_from_slice(key).ok()?;
+    Some(
+        aead.decrypt(GenericArray::from_slice(nonce), data)
+            .map_err(|_| ()),
+    )
+}
+
+/// Encryption of traditional PEM keys with the `Proc-Type` and `DEK-Info` headers of
+/// [RFC 1421 Section 4.6.1](https://datatracker.ietf.org/doc/html/rfc1421#section-4.6.1), as
+/// written by OpenSSL. The key is derived from the passphrase with `EVP_BytesToKey`.
+pub mod legacy {
+    use md5::{Digest, Md5};
+
+    use super::*;
+
+    /// Ciphers of `DEK-Info` by their OpenSSL names.
+    const CIPHERS: [(&str, Cipher); 4] = [
+        ("AES-128-CBC", Cipher::Aes128Cbc),
+        ("AES-192-CBC", Cipher::Aes192Cbc),
+        ("AES-256-CBC", Cipher::Aes256Cbc),
+        ("DES-EDE3-CBC", Cipher::DesEde3Cbc),
+    ];
+
+    #[derive(Serialize)]
+    pub struct EncryptedPemKey {
+        /// Cipher of the `DEK-Info` header, e.g. `AES-256-CBC`.
+        pub cipher: String,
+        pub iv: String,
+        #[serde(rename = "encryptedData")]
+        pub encrypted_data: Spanned<String>,
+        #[serde(skip)]
+        dek_info: (Cipher, Vec<u8>),
+    }
+
+    /// Decodes the hexadecimal IV of `DEK-Info`.
+    fn decode_hex(hex: &str) -> Option<Vec<u8>> {
+        if hex.len() % 2 != 0 || !hex.is_ascii() {
+            return None;
+        }
+        (0..hex.len())
+            .step_by(2)
+            .map(|i| u8::from_str_radix(&hex[i..i + 2], 16).ok())
+            .collect()
+    }
+
+    /// Parses the encryption headers of a PEM block whose content is `content`, or returns `None`
+    /// if the block is not encrypted.
+    pub fn parse(headers: &pem::HeaderMap, content: &[u8]) -> Result<Option<EncryptedPemKey>> {
+        match headers.get("Proc-Type") {
+            None => return Ok(None),
+            Some("4,ENCRYPTED") => {}
+            Some(_) => return Err(Error::PemHeaderError { name: "Proc-Type" }),
+        }
+        let invalid = || Error::PemHeaderError { name: "DEK-Info" };
+        let (name, iv) = headers
+            .get("DEK-Info")
+            .and_then(|value| value.split_once(','))
+            .ok_or_else(invalid)?;
+        let cipher = CIPHERS
+            .iter()
+            .find(|(known, _)| known.eq_ignore_ascii_case(name))
+            .map(|&(_, cipher)| cipher)
+            .ok_or_else(|| Error::UnsupportedCipher(name.to_owned()))?;
+        let iv = decode_hex(iv).ok_or_else(invalid)?;
+        // The first 8 bytes of the IV are also the salt of the key derivation.
+        if iv.len() != cipher.block_len() {
+            return Err(invalid());
+        }
+        Ok(Some(EncryptedPemKey {
+            cipher: name.to_owned(),
+            iv: fingerprint::colon_hex(&iv, false),
+            encrypted_data: Spanned::new(
+                format!("({} bytes)", content.len()),
+                Span::new(0, content.len()),
+            ),
+            dek_info: (cipher, iv),
+        }))
+    }
+
+    /// `EVP_BytesToKey` with MD5 and a single iteration, which OpenSSL uses for PEM encryption.
+    fn bytes_to_key(passphrase: &[u8], salt: &[u8], len: usize) -> Vec<u8> {
+        let mut key = Vec::with_capacity(len);
+        let mut block = vec![];
+        while key.len() < len {
+            let mut md5 = Md5::new();
+            md5.update(&block);
+            md5.update(passphrase);
+            md5.update(salt);
+            block = md5.finalize().to_vec();
+            key.extend_from_slice(&block);
+        }
+        key.truncate(len);
+        key
+    }
+
+    /// Decrypts the content `content` of the encrypted PEM block of `key` with `passphrase`.
+    pub fn decrypt(key: &EncryptedPemKey, content: &[u8], passphrase: &[u8]) -> Result<Vec<u8>> {
+        let (cipher, iv) = &key.dek_info;
+        if content.is_empty() || content.len() % cipher.block_len() != 0 {
+            return Err(Error::InvalidInputError {
+                field: "encryptedData",
+                span: key.encrypted_data.span,
+            });
+        }
+        let derived = bytes_to_key(passphrase, &iv[..8], cipher.key_len());
+        let plaintext = cipher
+            .decrypt_cbc(&derived, iv, content)
+            .expect("key and IV lengths match the cipher");
+        check_plaintext(plaintext)
+    }
+
+    #[cfg(test)]
+    mod tests {
+        use super::*;
+
+        #[test]
+        fn bytes_to_key_test() {
+            // openssl enc -aes-256-cbc -P -md md5 -S 0102030405060708 -pass pass:secret
+            let key = bytes_to_key(b"secret", &[1, 2, 3, 4, 5, 6, 7, 8], 32);
+            assert_eq!(
+                fingerprint::colon_hex(&key, true).replace(':', ""),
+                "C9E5A1BD216DBE1317E230CEF48F38EE7F0E17AD64022144BCCEC4A1AA2879AB"
+            );
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn tlv(tag: u8, content: &[u8]) -> Vec<u8> {
+        let mut der = match content.len() {
+            len @ 0..=0x7f => vec![tag, len as u8],
+            len => vec![tag, 0x81, len as u8],
+        };
+        der.extend_from_slice(content);
+        der
+    }
+
+    fn seq(parts: &[&[u8]]) -> Vec<u8> {
+        tlv(0x30, &parts.concat())
+    }
+
+    // OpenSSL cannot create keys encrypted with GCM, so one is built here.
+    #[test]
+    fn gcm_test() {
+        let (salt, nonce) = ([1; 8], [2; 12]);
+        let mut key = [0; 16];
+        pbkdf2::pbkdf2_hmac::<Sha256>(b"secret", &salt, 10, &mut key);
+        // An Ed25519 PrivateKeyInfo
+        let plaintext = seq(&[
+            &[0x02, 0x01, 0x00],
+            &seq(&[&[0x06, 0x03, 0x2b, 0x65, 0x70]]),
+            &tlv(0x04, &tlv(0x04, &[3; 32])),
+        ]);
+        let aead = AesGcm::<Aes128, U12, U16>::new_from_slice(&key).unwrap();
+        let ciphertext = aead
+            .encrypt(GenericArray::from_slice(&nonce), plaintext.as_slice())
+            .unwrap();
+        let oid = |oid: Oid| tlv(0x06, oid.as_bytes());
+        let pbkdf2 = seq(&[
+            &oid(OID_PBKDF2),
+            &seq(&[
+                &tlv(0x04, &salt),
+                &[0x02, 0x01, 10],
+                &seq(&[&oid(PRFS[2].0.clone()), &[0x05, 0x00]]),
+            ]),
+        ]);
+        let gcm = seq(&[
+            &oid(CIPHERS[3].0.clone()),
+            &seq(&[&tlv(0x04, &nonce), &[0x02, 0x01, 16]]),
+        ]);
+        let info = seq(&[
+            &seq(&[&oid(OID_PBES2), &seq(&[&pbkdf2, &gcm])]),
+            &tlv(0x04, &ciphertext),
+        ]);
+
+        let value = serde_json::to_value(parse(&info).unwrap()).unwrap();
+        let scheme = &value["encryptionAlgorithm"]["parameters"]["encryptionScheme"];
+        assert_eq!(scheme["algorithm"], "aes-128-gcm (2.16.840.1.101.3.4.1.6)");
+        assert_eq!(scheme["parameters"]["aes-ICVlen"]["value"], "0x10");
+        let parsed: EncryptedPrivateKeyInfoAsn1 = der::parse_der(&info, "test").unwrap();
+        assert_eq!(decrypt_info(&parsed, b"secret", &info).unwrap(), plaintext);
+        assert!(matches!(
+            decrypt_info(&parsed, b"wrong", &info),
+            Err(Error::WrongPassphrase)
+        ));
+    }
+
+    #[test]
+    fn kdf_limits_test() {
+        let oid = |oid: Oid| tlv(0x06, oid.as_bytes());
+        let info = |kdf: &[u8]| {
+            let cbc = seq(&[&oid(CIPHERS[0].0.clone()), &tlv(0x04, &[0; 16])]);
+            seq(&[
+                &seq(&[&oid(OID_PBES2), &seq(&[kdf, &cbc])]),
+                &tlv(0x04, &[0; 16]),
+            ])
+        };
+        let rejected = |kdf: &[u8]| {
+            let info = info(kdf);
+            let parsed: EncryptedPrivateKeyInfoAsn1 = der::parse_der(&info, "test").unwrap();
+            match decrypt_info(&parsed, b"secret", &info) {
+                Err(Error::UnsupportedAlgorithm { field, .. }) => field,
+                _ => panic!("not rejected"),
+            }
+        };
+        // iterationCount = 2^32 - 1
+        let pbkdf2 = seq(&[
+            &oid(OID_PBKDF2),
+            &seq(&[
+                &tlv(0x04, &[1; 8]),
+                &[0x02, 0x05, 0x00, 0xff, 0xff, 0xff, 0xff],
+            ]),
+        ]);
+        assert_eq!(rejected(&pbkdf2), "iterationCount");
+        // N = 2^40, r = 8 and p = 1, which would need 128 TiB
+        let scrypt = seq(&[
+            &oid(OID_SCRYPT),
+            &seq(&[
+                &tlv(0x04, &[1; 8]),
+                &[0x02, 0x06, 0x01, 0, 0, 0, 0, 0],
+                &[0x02, 0x01, 8],
+                &[0x02, 0x01, 1],
+            ]),
+        ]);
+        assert_eq!(rejected(&scrypt), "costParameter");
+    }
+}
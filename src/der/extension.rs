@@ -0,0 +1,622 @@
+//! X.509 v3 extensions.
+//!
+//! Extensions defined in [RFC 5280 Section 4.2](https://datatracker.ietf.org/doc/html/rfc5280#section-4.2)
+//! are decoded. Other extensions are displayed with their OID and length.
+#![allow(non_snake_case)]
+use std::net::{Ipv4Addr, Ipv6Addr};
+
+use asn1_rs::{oid, Any, BitString, Class, DerSequence, FromDer, OctetString, Oid, Sequence, Tag};
+use oid_registry::OidRegistry;
+use serde::Serialize;
+
+use crate::der::name::Name;
+use crate::der::object::Object;
+use crate::der::{self, Raw};
+use crate::error::{Error, Result};
+use crate::fingerprint;
+use crate::int::DisplayedInt;
+use crate::span::{Span, Spanned};
+
+// https://datatracker.ietf.org/doc/html/rfc5280#section-4.1
+#[derive(DerSequence)]
+pub(crate) struct ExtensionAsn1<'a> {
+    extnID: Oid<'a>,
+    #[default(false)]
+    critical: bool,
+    extnValue: OctetString<'a>,
+}
+
+#[derive(Serialize)]
+pub struct Extension {
+    #[serde(rename = "extnID")]
+    pub extn_id: Spanned<Object>,
+    pub critical: bool,
+    #[serde(rename = "extnValue")]
+    pub extn_value: Spanned<ExtensionValue>,
+    /// Why extnValue could not be decoded, in which case it is displayed as if the extension
+    /// were unknown.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+    pub span: Span,
+}
+
+/// Decoded value of an extension.
+#[derive(Serialize)]
+#[serde(untagged)]
+pub enum ExtensionValue {
+    BasicConstraints {
+        cA: bool,
+        pathLenConstraint: Option<u64>,
+    },
+    KeyUsage(KeyUsage),
+    /// extendedKeyUsage
+    Objects(Vec<Object>),
+    /// subjectAltName and issuerAltName
+    GeneralNames(Vec<GeneralName>),
+    /// subjectKeyIdentifier
+    KeyIdentifier(String),
+    AuthorityKeyIdentifier {
+        keyIdentifier: Option<String>,
+        authorityCertIssuer: Option<Vec<GeneralName>>,
+        authorityCertSerialNumber: Option<DisplayedInt>,
+    },
+    CrlDistributionPoints(Vec<DistributionPoint>),
+    AuthorityInfoAccess(Vec<AccessDescription>),
+    CertificatePolicies(Vec<PolicyInformation>),
+    /// nameConstraints. Only the bases of subtrees are displayed, because RFC 5280 forbids
+    /// `minimum` and `maximum`.
+    NameConstraints {
+        permittedSubtrees: Option<Vec<GeneralName>>,
+        excludedSubtrees: Option<Vec<GeneralName>>,
+    },
+    /// Summary of an extension that is not decoded.
+    Unknown(String),
+}
+
+/// The bits of keyUsage, which are displayed by the names of those that are set.
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub struct KeyUsage(u16);
+
+impl KeyUsage {
+    pub const DIGITAL_SIGNATURE: Self = Self(1 << 0);
+    pub const NON_REPUDIATION: Self = Self(1 << 1);
+    pub const KEY_ENCIPHERMENT: Self = Self(1 << 2);
+    pub const DATA_ENCIPHERMENT: Self = Self(1 << 3);
+    pub const KEY_AGREEMENT: Self = Self(1 << 4);
+    pub const KEY_CERT_SIGN: Self = Self(1 << 5);
+    pub const CRL_SIGN: Self = Self(1 << 6);
+    pub const ENCIPHER_ONLY: Self = Self(1 << 7);
+    pub const DECIPHER_ONLY: Self = Self(1 << 8);
+
+    fn from_bits(bits: &BitString) -> Self {
+        Self(
+            (0..KEY_USAGE.len())
+                .filter(|&i| bits.is_set(i))
+                .fold(0, |acc, i| acc | 1 << i),
+        )
+    }
+
+    pub fn is_empty(self) -> bool {
+        self.0 == 0
+    }
+
+    /// Whether any of the bits of `other` is set.
+    pub fn intersects(self, other: Self) -> bool {
+        self.0 & other.0 != 0
+    }
+
+    /// The bits that are set in `self` but not in `other`.
+    pub fn difference(self, other: Self) -> Self {
+        Self(self.0 & !other.0)
+    }
+
+    pub fn names(self) -> Vec<&'static str> {
+        (0..KEY_USAGE.len())
+            .filter(|&i| self.0 & 1 << i != 0)
+            .map(|i| KEY_USAGE[i])
+            .collect()
+    }
+}
+
+impl std::ops::BitOr for KeyUsage {
+    type Output = Self;
+
+    fn bitor(self, other: Self) -> Self {
+        Self(self.0 | other.0)
+    }
+}
+
+impl Serialize for KeyUsage {
+    fn serialize<S>(&self, serializer: S) -> std::result::Result<S::Ok, S::Error>
+    where
+        S: serde::Serializer,
+    {
+        serializer.collect_seq(self.names())
+    }
+}
+
+/// A GeneralName, with its CHOICE alternative in `type`.
+#[derive(Serialize)]
+pub struct GeneralName {
+    #[serde(rename = "type")]
+    pub ty: &'static str,
+    pub value: String,
+    pub span: Span,
+}
+
+#[derive(Serialize)]
+pub struct DistributionPoint {
+    #[serde(rename = "distributionPoint")]
+    pub distribution_point: Option<Vec<GeneralName>>,
+    pub reasons: Option<Vec<&'static str>>,
+    #[serde(rename = "cRLIssuer")]
+    pub crl_issuer: Option<Vec<GeneralName>>,
+}
+
+#[derive(Serialize)]
+pub struct AccessDescription {
+    #[serde(rename = "accessMethod")]
+    pub access_method: Object,
+    #[serde(rename = "accessLocation")]
+    pub access_location: GeneralName,
+}
+
+#[derive(Serialize)]
+pub struct PolicyInformation {
+    #[serde(rename = "policyIdentifier")]
+    pub policy_identifier: Object,
+    #[serde(rename = "policyQualifiers")]
+    pub policy_qualifiers: Vec<PolicyQualifierInfo>,
+}
+
+#[derive(Serialize)]
+pub struct PolicyQualifierInfo {
+    #[serde(rename = "policyQualifierId")]
+    pub policy_qualifier_id: Object,
+    /// The CPS URI, the explicit text of a user notice, or a summary.
+    pub qualifier: String,
+}
+
+const KEY_USAGE: [&str; 9] = [
+    "digitalSignature",
+    "nonRepudiation",
+    "keyEncipherment",
+    "dataEncipherment",
+    "keyAgreement",
+    "keyCertSign",
+    "cRLSign",
+    "encipherOnly",
+    "decipherOnly",
+];
+
+const REASON_FLAGS: [&str; 9] = [
+    "unused",
+    "keyCompromise",
+    "cACompromise",
+    "affiliationChanged",
+    "superseded",
+    "cessationOfOperation",
+    "certificateHold",
+    "privilegeWithdrawn",
+    "aACompromise",
+];
+
+/// State shared by the decoders of an extension.
+struct Decoder<'r> {
+    registry: &'r OidRegistry<'r>,
+    /// The DER from which the extension was parsed.
+    base: &'r [u8],
+    /// The span of extnValue, to which errors are attributed.
+    span: Span,
+}
+
+impl Decoder<'_> {
+    fn err(&self) -> impl FnOnce(asn1_rs::Error) -> Error {
+        Error::asn1("extnValue", self.span)
+    }
+
+    fn object(&self, oid: &Oid) -> Object {
+        (oid, self.registry.get(oid)).into()
+    }
+
+    /// Parses `content` as a sequence of TLVs.
+    fn children<'a>(&self, content: &'a [u8]) -> Result<Vec<Any<'a>>> {
+        let mut rem = content;
+        let mut children = vec![];
+        while !rem.is_empty() {
+            let (next, any) = Any::from_der(rem).map_err(|e| self.err()(e.into()))?;
+            children.push(any);
+            rem = next;
+        }
+        Ok(children)
+    }
+
+    /// Parses `content` as a single SEQUENCE and returns its elements.
+    fn sequence<'a>(&self, content: &'a [u8]) -> Result<Vec<Any<'a>>> {
+        let seq: Any =
+            der::parse_der(content, "extnValue").map_err(|e| e.shift(self.span.start))?;
+        seq.tag().assert_eq(Tag::Sequence).map_err(self.err())?;
+        self.children(seq.data)
+    }
+
+    fn flags(&self, bits: &BitString, names: &[&'static str]) -> Vec<&'static str> {
+        (0..names.len())
+            .filter(|&i| bits.is_set(i))
+            .map(|i| names[i])
+            .collect()
+    }
+
+    fn general_names(&self, content: &[u8]) -> Result<Vec<GeneralName>> {
+        self.children(content)?
+            .iter()
+            .map(|name| self.general_name(name))
+            .collect()
+    }
+
+    // https://datatracker.ietf.org/doc/html/rfc5280#section-4.2.1.6
+    // The module uses implicit tags, except for CHOICE types such as Name.
+    fn general_name(&self, name: &Any) -> Result<GeneralName> {
+        if name.class() != Class::ContextSpecific {
+            return Err(self.err()(asn1_rs::Error::unexpected_class(
+                Some(Class::ContextSpecific),
+                name.class(),
+            )));
+        }
+        let span = der::any_span(self.base, name);
+        let ia5 = |ty| -> Result<GeneralName> {
+            let value = std::str::from_utf8(name.data)
+                .ok()
+                .filter(|s| s.is_ascii())
+                .ok_or_else(|| self.err()(asn1_rs::Error::StringInvalidCharset))?;
+            Ok(GeneralName {
+                span,
+                ty,
+                value: value.to_owned(),
+            })
+        };
+        let summary = |ty| GeneralName {
+            span,
+            ty,
+            value: format!("({} bytes)", name.data.len()),
+        };
+        Ok(match name.tag().0 {
+            0 => {
+                let children = self.children(name.data)?;
+                let value = match children.first().map(Oid::try_from) {
+                    Some(Ok(type_id)) => format!("({})", self.object(&type_id)),
+                    _ => format!("({} bytes)", name.data.len()),
+                };
+                GeneralName {
+                    span,
+                    ty: "otherName",
+                    value,
+                }
+            }
+            1 => ia5("rfc822Name")?,
+            2 => ia5("dNSName")?,
+            3 => summary("x400Address"),
+            4 => {
+                let seq: Sequence = der::parse_der(name.data, "directoryName")
+                    .map_err(|e| e.shift(der::span_in(self.base, name.data).start))?;
+                let name = Name::from(&seq, self.registry, self.base, "directoryName")?;
+                GeneralName {
+                    span,
+                    ty: "directoryName",
+                    value: name.rfc4514,
+                }
+            }
+            5 => summary("ediPartyName"),
+            6 => ia5("uniformResourceIdentifier")?,
+            7 => GeneralName {
+                span,
+                ty: "iPAddress",
+                value: ip_address(name.data).unwrap_or_else(|| fingerprint::hex(name.data)),
+            },
+            8 => {
+                let oid = Oid::new(name.data.into());
+                GeneralName {
+                    span,
+                    ty: "registeredID",
+                    value: self.object(&oid).to_string(),
+                }
+            }
+            _ => return Err(self.err()(asn1_rs::Error::BerValueError)),
+        })
+    }
+
+    /// Finds the optional elements with context-specific tags in `elements`.
+    fn tagged<'a, 'b>(&self, elements: &'b [Any<'a>], tag: u32) -> Option<&'b Any<'a>> {
+        elements
+            .iter()
+            .find(|x| x.class() == Class::ContextSpecific && x.tag().0 == tag)
+    }
+
+    fn basic_constraints(&self, content: &[u8]) -> Result<ExtensionValue> {
+        let elements = self.sequence(content)?;
+        let mut elements = elements.iter().peekable();
+        let cA = match elements.next_if(|x| x.tag() == Tag::Boolean) {
+            Some(x) => x.as_bool().map_err(self.err())?,
+            None => false,
+        };
+        let pathLenConstraint = elements
+            .next()
+            .map(|x| x.as_u64().map_err(self.err()))
+            .transpose()?;
+        Ok(ExtensionValue::BasicConstraints {
+            cA,
+            pathLenConstraint,
+        })
+    }
+
+    fn authority_key_identifier(&self, content: &[u8]) -> Result<ExtensionValue> {
+        let elements = self.sequence(content)?;
+        let serial = self.tagged(&elements, 2).map(|x| {
+            let int = asn1_rs::Integer::new(x.data);
+            DisplayedInt::new(int.as_bigint(), der::any_span(self.base, x))
+        });
+        Ok(ExtensionValue::AuthorityKeyIdentifier {
+            keyIdentifier: self.tagged(&elements, 0).map(|x| key_identifier(x.data)),
+            authorityCertIssuer: self
+                .tagged(&elements, 1)
+                .map(|x| self.general_names(x.data))
+                .transpose()?,
+            authorityCertSerialNumber: serial,
+        })
+    }
+
+    fn crl_distribution_points(&self, content: &[u8]) -> Result<ExtensionValue> {
+        let mut points = vec![];
+        for point in self.sequence(content)? {
+            let elements = self.children(point.data)?;
+            let distribution_point = match self.tagged(&elements, 0) {
+                Some(name) => {
+                    // DistributionPointName is a CHOICE, so it is explicitly tagged.
+                    let children = self.children(name.data)?;
+                    let name = children
+                        .first()
+                        .ok_or_else(|| self.err()(asn1_rs::Error::BerValueError))?;
+                    Some(match name.tag().0 {
+                        0 => self.general_names(name.data)?,
+                        _ => vec![GeneralName {
+                            ty: "nameRelativeToCRLIssuer",
+                            value: format!("({} bytes)", name.data.len()),
+                            span: der::any_span(self.base, name),
+                        }],
+                    })
+                }
+                None => None,
+            };
+            let reasons = match self.tagged(&elements, 1) {
+                Some(x) => {
+                    let bits = reason_flags(x).map_err(self.err())?;
+                    Some(self.flags(&bits, &REASON_FLAGS))
+                }
+                None => None,
+            };
+            points.push(DistributionPoint {
+                distribution_point,
+                reasons,
+                crl_issuer: self
+                    .tagged(&elements, 2)
+                    .map(|x| self.general_names(x.data))
+                    .transpose()?,
+            });
+        }
+        Ok(ExtensionValue::CrlDistributionPoints(points))
+    }
+
+    fn authority_info_access(&self, content: &[u8]) -> Result<ExtensionValue> {
+        let mut descriptions = vec![];
+        for description in self.sequence(content)? {
+            let elements = self.children(description.data)?;
+            let [method, location] = elements.as_slice() else {
+                return Err(self.err()(asn1_rs::Error::InvalidLength));
+            };
+            let method = Oid::try_from(method).map_err(self.err())?;
+            descriptions.push(AccessDescription {
+                access_method: self.object(&method),
+                access_location: self.general_name(location)?,
+            });
+        }
+        Ok(ExtensionValue::AuthorityInfoAccess(descriptions))
+    }
+
+    fn certificate_policies(&self, content: &[u8]) -> Result<ExtensionValue> {
+        let mut policies = vec![];
+        for policy in self.sequence(content)? {
+            let elements = self.children(policy.data)?;
+            let identifier = elements
+                .first()
+                .ok_or_else(|| self.err()(asn1_rs::Error::InvalidLength))?;
+            let identifier = Oid::try_from(identifier).map_err(self.err())?;
+            let mut qualifiers = vec![];
+            if let Some(infos) = elements.get(1) {
+                for info in self.children(infos.data)? {
+                    let elements = self.children(info.data)?;
+                    let [id, qualifier] = elements.as_slice() else {
+                        return Err(self.err()(asn1_rs::Error::InvalidLength));
+                    };
+                    let id = Oid::try_from(id).map_err(self.err())?;
+                    qualifiers.push(PolicyQualifierInfo {
+                        policy_qualifier_id: self.object(&id),
+                        qualifier: self.policy_qualifier(qualifier)?,
+                    });
+                }
+            }
+            policies.push(PolicyInformation {
+                policy_identifier: self.object(&identifier),
+                policy_qualifiers: qualifiers,
+            });
+        }
+        Ok(ExtensionValue::CertificatePolicies(policies))
+    }
+
+    // https://datatracker.ietf.org/doc/html/rfc5280#section-4.2.1.4
+    fn policy_qualifier(&self, qualifier: &Any) -> Result<String> {
+        let summary = || format!("({} bytes)", qualifier.data.len());
+        if qualifier.tag() == Tag::Ia5String {
+            // CPSuri
+            return Ok(qualifier.as_any_str().unwrap_or_else(|_| summary()));
+        }
+        if qualifier.tag() == Tag::Sequence {
+            // UserNotice: the explicit text is the only element that is not a SEQUENCE.
+            let text = self
+                .children(qualifier.data)?
+                .into_iter()
+                .find(|x| x.tag() != Tag::Sequence)
+                .and_then(|x| x.as_any_str().ok());
+            return Ok(text.unwrap_or_else(summary));
+        }
+        Ok(summary())
+    }
+
+    fn name_constraints(&self, content: &[u8]) -> Result<ExtensionValue> {
+        let elements = self.sequence(content)?;
+        let subtrees = |tag| -> Result<Option<Vec<GeneralName>>> {
+            let Some(subtrees) = self.tagged(&elements, tag) else {
+                return Ok(None);
+            };
+            let mut bases = vec![];
+            for subtree in self.children(subtrees.data)? {
+                let elements = self.children(subtree.data)?;
+                let base = elements
+                    .first()
+                    .ok_or_else(|| self.err()(asn1_rs::Error::InvalidLength))?;
+                bases.push(self.general_name(base)?);
+            }
+            Ok(Some(bases))
+        };
+        Ok(ExtensionValue::NameConstraints {
+            permittedSubtrees: subtrees(0)?,
+            excludedSubtrees: subtrees(1)?,
+        })
+    }
+
+    /// Decodes the value of the extension `id`, or summarizes it if the extension is unknown.
+    fn value(&self, id: &Oid, content: &[u8]) -> Result<ExtensionValue> {
+        Ok(if *id == oid!(2.5.29 .19) {
+            self.basic_constraints(content)?
+        } else if *id == oid!(2.5.29 .15) {
+            let (_, bits) = BitString::from_der(content).map_err(|e| self.err()(e.into()))?;
+            ExtensionValue::KeyUsage(KeyUsage::from_bits(&bits))
+        } else if *id == oid!(2.5.29 .37) {
+            let purposes = self
+                .sequence(content)?
+                .iter()
+                .map(|x| Ok(self.object(&Oid::try_from(x).map_err(self.err())?)))
+                .collect::<Result<_>>()?;
+            ExtensionValue::Objects(purposes)
+        } else if *id == oid!(2.5.29 .17) || *id == oid!(2.5.29 .18) {
+            let names = self.sequence(content)?;
+            ExtensionValue::GeneralNames(
+                names
+                    .iter()
+                    .map(|x| self.general_name(x))
+                    .collect::<Result<_>>()?,
+            )
+        } else if *id == oid!(2.5.29 .14) {
+            let (_, key_id) = OctetString::from_der(content).map_err(|e| self.err()(e.into()))?;
+            ExtensionValue::KeyIdentifier(key_identifier(key_id.as_cow()))
+        } else if *id == oid!(2.5.29 .35) {
+            self.authority_key_identifier(content)?
+        } else if *id == oid!(2.5.29 .31) {
+            self.crl_distribution_points(content)?
+        } else if *id == oid!(1.3.6 .1 .5 .5 .7 .1 .1) {
+            self.authority_info_access(content)?
+        } else if *id == oid!(2.5.29 .32) {
+            self.certificate_policies(content)?
+        } else if *id == oid!(2.5.29 .30) {
+            self.name_constraints(content)?
+        } else {
+            unknown(content)
+        })
+    }
+}
+
+fn unknown(content: &[u8]) -> ExtensionValue {
+    ExtensionValue::Unknown(format!("({} bytes)", content.len()))
+}
+
+/// Describes why a value could not be decoded, including the underlying ASN.1 error.
+fn message(error: &Error) -> String {
+    match std::error::Error::source(error) {
+        Some(source) => format!("{}: {}", error, source),
+        None => error.to_string(),
+    }
+}
+
+/// ReasonFlags is implicitly tagged, so its tag is replaced before it is decoded.
+fn reason_flags<'a>(x: &Any<'a>) -> asn1_rs::Result<BitString<'a>> {
+    let any = x
+        .clone()
+        .with_class(Class::Universal)
+        .with_tag(Tag::BitString);
+    BitString::try_from(any)
+}
+
+fn key_identifier(bytes: &[u8]) -> String {
+    fingerprint::colon_hex(bytes, true)
+}
+
+/// Formats an IP address, or an address and a mask in name constraints.
+fn ip_address(bytes: &[u8]) -> Option<String> {
+    let v4 = |b: &[u8]| Ipv4Addr::from(<[u8; 4]>::try_from(b).unwrap()).to_string();
+    let v6 = |b: &[u8]| Ipv6Addr::from(<[u8; 16]>::try_from(b).unwrap()).to_string();
+    Some(match bytes.len() {
+        4 => v4(bytes),
+        8 => format!("{}/{}", v4(&bytes[..4]), v4(&bytes[4..])),
+        16 => v6(bytes),
+        32 => format!("{}/{}", v6(&bytes[..16]), v6(&bytes[16..])),
+        _ => return None,
+    })
+}
+
+impl Extension {
+    pub(crate) fn from(
+        raw: &Raw<ExtensionAsn1>,
+        registry: &OidRegistry,
+        base: &[u8],
+    ) -> Result<Self> {
+        let value = &raw.value;
+        let content = value.extnValue.as_cow();
+        let span = der::span_in(base, content);
+        let decoder = Decoder {
+            registry,
+            base,
+            span,
+        };
+        let id = &value.extnID;
+        // A malformed value of a known extension is displayed as if the extension were unknown,
+        // so that the rest of the certificate can still be read.
+        let (extn_value, error) = match decoder.value(id, content) {
+            Ok(extn_value) => (extn_value, None),
+            Err(e) => (unknown(content), Some(message(&e))),
+        };
+        Ok(Self {
+            extn_id: Spanned::new(decoder.object(id), der::tlv_span(base, id.as_bytes())),
+            critical: value.critical,
+            extn_value: Spanned::new(extn_value, der::tlv_span(base, content)),
+            error,
+            span: raw.span(base),
+        })
+    }
+}
+
+/// Decodes Extensions, a SEQUENCE of Extension.
+pub(crate) fn parse_extensions(
+    extensions: &Any,
+    registry: &OidRegistry,
+    base: &[u8],
+) -> Result<Vec<Extension>> {
+    let span = der::span_in(base, extensions.data);
+    let err = |e: asn1_rs::Error| Error::asn1("extensions", span)(e);
+    extensions.tag().assert_eq(Tag::Sequence).map_err(err)?;
+    let mut rem = extensions.data;
+    let mut decoded = vec![];
+    while !rem.is_empty() {
+        let (next, extension) = Raw::<ExtensionAsn1>::from_der(rem).map_err(|e| err(e.into()))?;
+        decoded.push(Extension::from(&extension, registry, base)?);
+        rem = next;
+    }
+    Ok(decoded)
+}
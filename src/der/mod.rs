@@ -1,9 +1,257 @@
+use asn1_rs::{Any, CheckDerConstraints, FromBer, FromDer, Integer, ParseResult, Tag, Tagged};
+
+use crate::error::{Error, Result};
+use crate::int::DisplayedInt;
+use crate::span::Span;
+
+pub mod algorithm;
+pub mod attribute;
 pub mod cert;
 pub mod csr;
+pub mod dsa;
+pub mod ec;
 pub mod ed;
+pub mod encrypted;
+pub mod extension;
+pub mod name;
 pub mod object;
 pub mod privkey;
 pub mod pubkey;
 /// Custom OID registry.
 pub mod registry;
 pub mod rsa;
+pub mod validity;
+
+/// Parses `content` as a single `T`. Failures are reported as failures in `field`.
+pub(crate) fn parse_der<'a, T>(content: &'a [u8], field: &'static str) -> Result<T>
+where
+    T: FromDer<'a, asn1_rs::Error>,
+{
+    let (rem, value) =
+        T::from_der(content).map_err(|e| Error::asn1(field, locate_error(content))(e.into()))?;
+    if !rem.is_empty() {
+        let start = content.len() - rem.len();
+        return Err(Error::ParseError {
+            field: "trailing data",
+            span: Span::new(start, content.len()),
+        });
+    }
+    Ok(value)
+}
+
+/// A value along with the DER encoding from which it was parsed, e.g. for hashing or verifying it.
+#[derive(Debug)]
+pub(crate) struct Raw<'a, T> {
+    pub(crate) der: &'a [u8],
+    pub(crate) value: T,
+}
+
+impl<T> Raw<'_, T> {
+    /// Returns the span of the whole TLV in `base`.
+    pub(crate) fn span(&self, base: &[u8]) -> Span {
+        span_in(base, self.der)
+    }
+}
+
+impl<'a, T: FromDer<'a, asn1_rs::Error>> FromDer<'a, asn1_rs::Error> for Raw<'a, T> {
+    fn from_der(bytes: &'a [u8]) -> ParseResult<'a, Self, asn1_rs::Error> {
+        let (rem, value) = T::from_der(bytes)?;
+        let der = &bytes[..bytes.len() - rem.len()];
+        Ok((rem, Raw { der, value }))
+    }
+}
+
+impl<'a, T: FromBer<'a, asn1_rs::Error>> FromBer<'a, asn1_rs::Error> for Raw<'a, T> {
+    fn from_ber(bytes: &'a [u8]) -> ParseResult<'a, Self, asn1_rs::Error> {
+        let (rem, value) = T::from_ber(bytes)?;
+        let der = &bytes[..bytes.len() - rem.len()];
+        Ok((rem, Raw { der, value }))
+    }
+}
+
+impl<T: CheckDerConstraints> CheckDerConstraints for Raw<'_, T> {
+    fn check_constraints(any: &Any) -> asn1_rs::Result<()> {
+        T::check_constraints(any)
+    }
+}
+
+impl<T: Tagged> Tagged for Raw<'_, T> {
+    const TAG: Tag = T::TAG;
+}
+
+/// Returns the span of `sub`, which must be a subslice of `base`, in `base`.
+///
+/// Passing another slice is a bug, which fails an assertion in debug builds. Release builds
+/// return the span of the whole `base` instead.
+pub(crate) fn span_in(base: &[u8], sub: &[u8]) -> Span {
+    let start = (sub.as_ptr() as usize).wrapping_sub(base.as_ptr() as usize);
+    let within = start <= base.len() && sub.len() <= base.len() - start;
+    debug_assert!(within, "the slice is not within the base");
+    if !within {
+        return Span::new(0, base.len());
+    }
+    Span::new(start, start + sub.len())
+}
+
+/// Returns the span in `base` of the whole TLV whose content is `content`, a subslice of `base`.
+///
+/// The header is assumed to be in its DER form with a single-byte tag, which holds for every
+/// universal type. Values with other tags are located with [`any_span`].
+pub(crate) fn tlv_span(base: &[u8], content: &[u8]) -> Span {
+    with_header(span_in(base, content), 1)
+}
+
+/// Returns the span in `base` of the whole TLV `value`, whose identifier may have several
+/// octets if its tag number is 31 or more.
+pub(crate) fn any_span(base: &[u8], value: &Any) -> Span {
+    with_header(span_in(base, value.data), identifier_len(value.tag()))
+}
+
+/// Returns the length of the DER identifier octets of `tag`: one octet for tag numbers below 31,
+/// and otherwise one more for each base-128 digit of the number.
+fn identifier_len(tag: Tag) -> usize {
+    if tag.0 < 0x1f {
+        1
+    } else {
+        1 + (u32::BITS - tag.0.leading_zeros() + 6) as usize / 7
+    }
+}
+
+/// Returns the span in `base` of the whole INTEGER `value`.
+pub(crate) fn int_span(base: &[u8], value: &Integer) -> Span {
+    any_span(base, &value.any())
+}
+
+/// Returns `value`, parsed from `base`, as a [`DisplayedInt`] spanning its whole TLV.
+pub(crate) fn displayed_int(base: &[u8], value: &Integer) -> DisplayedInt {
+    DisplayedInt::new(value.as_bigint(), int_span(base, value))
+}
+
+/// Returns the span in `base` of the whole BIT STRING whose data (after the unused bits) is `data`.
+pub(crate) fn bit_string_span(base: &[u8], data: &[u8]) -> Span {
+    let data = span_in(base, data);
+    with_header(Span::new(data.start.saturating_sub(1), data.end), 1)
+}
+
+fn with_header(content: Span, identifier_len: usize) -> Span {
+    let len = content.len();
+    let len_len = if len < 0x80 {
+        1
+    } else {
+        1 + (usize::BITS - len.leading_zeros() + 7) as usize / 8
+    };
+    Span::new(
+        content.start.saturating_sub(identifier_len + len_len),
+        content.end,
+    )
+}
+
+/// Finds the first TLV in `content` whose header or length is malformed.
+///
+/// If every TLV is well-formed, the span of the whole `content` is returned.
+pub(crate) fn locate_error(content: &[u8]) -> Span {
+    find_malformed(content).unwrap_or(Span::new(0, content.len()))
+}
+
+/// Finds the first TLV in `content` whose header or length is malformed, if any.
+pub(crate) fn find_malformed(content: &[u8]) -> Option<Span> {
+    locate_error_in(content, 0)
+}
+
+fn locate_error_in(content: &[u8], offset: usize) -> Option<Span> {
+    let mut pos = 0;
+    while pos < content.len() {
+        let malformed = Some(Span::new(offset + pos, offset + content.len()));
+        let constructed = content[pos] & 0x20 != 0;
+        let mut header_len = 1;
+        if content[pos] & 0x1f == 0x1f {
+            // high tag number form
+            while content.get(pos + header_len)? & 0x80 != 0 {
+                header_len += 1;
+            }
+            header_len += 1;
+        }
+        let Some(&first) = content.get(pos + header_len) else {
+            return malformed;
+        };
+        header_len += 1;
+        let len = if first < 0x80 {
+            first as usize
+        } else {
+            let num = (first & 0x7f) as usize;
+            // indefinite lengths are not allowed in DER
+            if num == 0 || num > std::mem::size_of::<usize>() {
+                return malformed;
+            }
+            let Some(bytes) = content.get(pos + header_len..pos + header_len + num) else {
+                return malformed;
+            };
+            header_len += num;
+            bytes.iter().fold(0, |acc, &b| acc << 8 | b as usize)
+        };
+        let start = pos + header_len;
+        if len > content.len() - start {
+            // Report the innermost truncated TLV
+            if constructed {
+                return locate_error_in(&content[start..], offset + start).or(malformed);
+            }
+            return malformed;
+        }
+        if constructed {
+            if let Some(span) = locate_error_in(&content[start..start + len], offset + start) {
+                return Some(span);
+            }
+        }
+        pos = start + len;
+    }
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn tlv_span_test() {
+        // SEQUENCE { INTEGER 1, OCTET STRING (200 bytes) }
+        let mut der = vec![0x30, 0x81, 0xce, 0x02, 0x01, 0x01, 0x04, 0x81, 0xc8];
+        der.extend_from_slice(&[0; 200]);
+        assert_eq!(tlv_span(&der, &der[5..6]), Span::new(3, 6));
+        assert_eq!(tlv_span(&der, &der[9..]), Span::new(6, 209));
+        assert_eq!(tlv_span(&der, &der[3..]), Span::new(0, 209));
+    }
+
+    #[test]
+    fn any_span_test() {
+        // SEQUENCE { [31] IMPLICIT INTEGER 1, [200] IMPLICIT INTEGER 2 }
+        let der = [
+            0x30, 0x09, 0x9f, 0x1f, 0x01, 0x01, 0x9f, 0x81, 0x48, 0x01, 0x02,
+        ];
+        let (rem, first) = Any::from_der(&der[2..]).unwrap();
+        let (_, second) = Any::from_der(rem).unwrap();
+        assert_eq!(first.tag(), Tag(31));
+        assert_eq!(any_span(&der, &first), Span::new(2, 6));
+        assert_eq!(second.tag(), Tag(200));
+        assert_eq!(any_span(&der, &second), Span::new(6, 11));
+    }
+
+    #[test]
+    fn locate_error_test_truncated() {
+        // SEQUENCE { INTEGER 1, OCTET STRING (declared 4 bytes, only 2 present) }
+        let content = [0x30, 0x07, 0x02, 0x01, 0x01, 0x04, 0x04, 0xaa, 0xbb];
+        assert_eq!(locate_error(&content), Span::new(5, 9));
+    }
+
+    #[test]
+    fn locate_error_test_truncated_outer() {
+        // SEQUENCE (declared 16 bytes) { INTEGER 1, OCTET STRING (declared 4 bytes, 2 present) }
+        let content = [0x30, 0x10, 0x02, 0x01, 0x01, 0x04, 0x04, 0xaa, 0xbb];
+        assert_eq!(locate_error(&content), Span::new(5, 9));
+    }
+
+    #[test]
+    fn locate_error_test_well_formed() {
+        let content = [0x30, 0x03, 0x02, 0x01, 0x01];
+        assert_eq!(locate_error(&content), Span::new(0, 5));
+    }
+}
@@ -0,0 +1,333 @@
+//! Distinguished names.
+//!
+//! Names are displayed both as RDN lists and in the string form of
+//! [RFC 4514](https://datatracker.ietf.org/doc/html/rfc4514).
+use asn1_rs::{Any, DerSequence, Oid, Sequence, Set, Tag, ToDer};
+use oid_registry::OidRegistry;
+use serde::Serialize;
+
+use crate::der::object::Object;
+use crate::der::{self, Raw};
+use crate::error::{Error, Result};
+use crate::fingerprint;
+use crate::span::{Span, Spanned};
+
+// https://www.itu.int/ITU-T/formal-language/itu-t/x/x501/2012/InformationFramework.html#InformationFramework.AttributeTypeAndValue
+#[derive(DerSequence)]
+struct AttributeTypeAndValueAsn1<'a> {
+    r#type: Oid<'a>,
+    value: Any<'a>,
+}
+
+#[derive(Serialize)]
+pub struct AttributeValue {
+    /// The decoded value, or `#` followed by the hex of the DER if its syntax is not supported.
+    pub value: String,
+    /// The ASN.1 type of the value, e.g. `PrintableString`.
+    pub string_type: &'static str,
+    /// Why the type of the value is not permitted for the attribute, if it is not.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub violation: Option<String>,
+    pub span: Span,
+}
+
+#[derive(Serialize)]
+pub struct AttributeTypeAndValue {
+    #[serde(rename = "type")]
+    pub ty: Spanned<Object>,
+    pub value: AttributeValue,
+    /// RFC 4514 Section 2.3 string form.
+    #[serde(skip)]
+    rfc4514: String,
+    pub span: Span,
+}
+
+impl AttributeTypeAndValue {
+    fn from(raw: Raw<AttributeTypeAndValueAsn1>, registry: &OidRegistry, base: &[u8]) -> Self {
+        let x = &raw.value;
+        let entry = registry.get(&x.r#type);
+        // RFC 4514 Section 2.4: values of attributes without short names are in the hex form.
+        let hex_form = || {
+            format!(
+                "#{}",
+                fingerprint::hex(&x.value.to_der_vec().unwrap_or_default())
+            )
+        };
+        let decoded = decode_string(&x.value);
+        let name = entry.map_or_else(|| x.r#type.to_id_string(), |e| e.sn().to_string());
+        let violation = permitted_types(&x.r#type)
+            .filter(|(types, _)| !types.contains(&x.value.tag()))
+            .map(|(_, expected)| format!("{} must be {}", name, expected));
+        let rfc4514 = match (short_name(&x.r#type), &decoded) {
+            (Some(name), Some(decoded)) => format!("{}={}", name, escape(decoded)),
+            (Some(name), None) => format!("{}={}", name, hex_form()),
+            (None, _) => format!("{}={}", x.r#type.to_id_string(), hex_form()),
+        };
+        Self {
+            ty: Spanned::new(
+                (&x.r#type, entry).into(),
+                der::tlv_span(base, x.r#type.as_bytes()),
+            ),
+            value: AttributeValue {
+                value: decoded
+                    .or_else(|| decode_other(&x.value))
+                    .unwrap_or_else(hex_form),
+                string_type: type_name(x.value.tag()),
+                violation,
+                span: der::any_span(base, &x.value),
+            },
+            rfc4514,
+            span: raw.span(base),
+        }
+    }
+}
+
+/// A decoded Name (X.501), serialized as its RDN list. Structures with names serialize the
+/// RFC 4514 string form next to it.
+#[derive(Serialize)]
+#[serde(transparent)]
+pub struct Name {
+    pub rdn_sequence: Vec<Vec<AttributeTypeAndValue>>,
+    /// RFC 4514 string form.
+    #[serde(skip)]
+    pub rfc4514: String,
+    #[serde(skip)]
+    pub span: Span,
+}
+
+impl Name {
+    /// Decodes `value`. `base` is the DER from which `value` was parsed, and `field` is the
+    /// name reported on failures.
+    pub(crate) fn from(
+        value: &Sequence,
+        registry: &OidRegistry,
+        base: &[u8],
+        field: &'static str,
+    ) -> Result<Self> {
+        let span = der::tlv_span(base, &value.content);
+        let rdn_sequence: Vec<Vec<AttributeTypeAndValue>> = value
+            .der_iter()
+            .map(|x| {
+                let set: Set = x?;
+                let val: Vec<_> = set
+                    .der_iter()
+                    .map(|x| Ok(AttributeTypeAndValue::from(x?, registry, base)))
+                    .collect::<asn1_rs::Result<Vec<_>>>()?;
+                Ok(val)
+            })
+            .collect::<asn1_rs::Result<Vec<_>>>()
+            .map_err(Error::asn1(field, span))?;
+        // RFC 4514 Section 2.1: the last RDN comes first.
+        let rfc4514 = rdn_sequence
+            .iter()
+            .rev()
+            .map(|rdn| {
+                rdn.iter()
+                    .map(|x| x.rfc4514.as_str())
+                    .collect::<Vec<_>>()
+                    .join("+")
+            })
+            .collect::<Vec<_>>()
+            .join(",");
+        Ok(Self {
+            rdn_sequence,
+            rfc4514,
+            span,
+        })
+    }
+}
+
+/// Short names defined in RFC 4514 Section 3.
+fn short_name(oid: &Oid) -> Option<&'static str> {
+    let names = [
+        (asn1_rs::oid!(2.5.4 .3), "CN"),
+        (asn1_rs::oid!(2.5.4 .7), "L"),
+        (asn1_rs::oid!(2.5.4 .8), "ST"),
+        (asn1_rs::oid!(2.5.4 .10), "O"),
+        (asn1_rs::oid!(2.5.4 .11), "OU"),
+        (asn1_rs::oid!(2.5.4 .6), "C"),
+        (asn1_rs::oid!(2.5.4 .9), "STREET"),
+        (asn1_rs::oid!(0.9.2342 .19200300 .100 .1 .25), "DC"),
+        (asn1_rs::oid!(0.9.2342 .19200300 .100 .1 .1), "UID"),
+    ];
+    names
+        .into_iter()
+        .find(|(known, _)| known == oid)
+        .map(|(_, name)| name)
+}
+
+/// The types of DirectoryString (RFC 5280 Appendix A.1).
+const DIRECTORY_STRING: &[Tag] = &[
+    Tag::TeletexString,
+    Tag::PrintableString,
+    Tag::UniversalString,
+    Tag::Utf8String,
+    Tag::BmpString,
+];
+
+/// Returns the types permitted for values of the attribute `oid` and their description, if the
+/// attribute is one of those of RFC 5280 Appendix A.1, or of X.520 used in certificates.
+fn permitted_types(oid: &Oid) -> Option<(&'static [Tag], &'static str)> {
+    let printable: (&[Tag], &str) = (&[Tag::PrintableString], "a PrintableString");
+    let ia5: (&[Tag], &str) = (&[Tag::Ia5String], "an IA5String");
+    let directory: (&[Tag], &str) = (DIRECTORY_STRING, "a DirectoryString");
+    let types = [
+        // countryName, serialNumber, dnQualifier
+        (asn1_rs::oid!(2.5.4 .6), printable),
+        (asn1_rs::oid!(2.5.4 .5), printable),
+        (asn1_rs::oid!(2.5.4 .46), printable),
+        // jurisdictionCountryName of EV certificates
+        (asn1_rs::oid!(1.3.6 .1 .4 .1 .311 .60 .2 .1 .3), printable),
+        // emailAddress, domainComponent
+        (asn1_rs::oid!(1.2.840 .113549 .1 .9 .1), ia5),
+        (asn1_rs::oid!(0.9.2342 .19200300 .100 .1 .25), ia5),
+        // commonName, surname, localityName, stateOrProvinceName, streetAddress,
+        // organizationName, organizationalUnitName, title, businessCategory, postalCode,
+        // givenName, initials, generationQualifier, pseudonym
+        (asn1_rs::oid!(2.5.4 .3), directory),
+        (asn1_rs::oid!(2.5.4 .4), directory),
+        (asn1_rs::oid!(2.5.4 .7), directory),
+        (asn1_rs::oid!(2.5.4 .8), directory),
+        (asn1_rs::oid!(2.5.4 .9), directory),
+        (asn1_rs::oid!(2.5.4 .10), directory),
+        (asn1_rs::oid!(2.5.4 .11), directory),
+        (asn1_rs::oid!(2.5.4 .12), directory),
+        (asn1_rs::oid!(2.5.4 .15), directory),
+        (asn1_rs::oid!(2.5.4 .17), directory),
+        (asn1_rs::oid!(2.5.4 .42), directory),
+        (asn1_rs::oid!(2.5.4 .43), directory),
+        (asn1_rs::oid!(2.5.4 .44), directory),
+        (asn1_rs::oid!(2.5.4 .65), directory),
+    ];
+    types
+        .into_iter()
+        .find(|(known, _)| known == oid)
+        .map(|(_, types)| types)
+}
+
+/// Name of the ASN.1 type of values with `tag`.
+fn type_name(tag: Tag) -> &'static str {
+    match tag {
+        Tag::Boolean => "BOOLEAN",
+        Tag::Integer => "INTEGER",
+        Tag::BitString => "BIT STRING",
+        Tag::OctetString => "OCTET STRING",
+        Tag::Oid => "OBJECT IDENTIFIER",
+        Tag::Utf8String => "UTF8String",
+        Tag::Sequence => "SEQUENCE",
+        Tag::Set => "SET",
+        Tag::NumericString => "NumericString",
+        Tag::PrintableString => "PrintableString",
+        Tag::TeletexString => "TeletexString",
+        Tag::Ia5String => "IA5String",
+        Tag::UtcTime => "UTCTime",
+        Tag::GeneralizedTime => "GeneralizedTime",
+        Tag::VisibleString => "VisibleString",
+        Tag::UniversalString => "UniversalString",
+        Tag::BmpString => "BMPString",
+        _ => "unknown",
+    }
+}
+
+/// Decodes a value of one of the syntaxes of attributes other than strings, e.g. the
+/// GeneralizedTime of dateOfBirth (RFC 3739 Section 3.2.2).
+fn decode_other(value: &Any) -> Option<String> {
+    match value.tag() {
+        Tag::UtcTime | Tag::GeneralizedTime => value
+            .data
+            .is_ascii()
+            .then(|| String::from_utf8_lossy(value.data).into_owned()),
+        Tag::Integer => Some(value.clone().integer().ok()?.as_bigint().to_string()),
+        Tag::Boolean => Some(
+            if value.clone().bool().ok()? {
+                "TRUE"
+            } else {
+                "FALSE"
+            }
+            .to_string(),
+        ),
+        Tag::Oid => Some(value.clone().oid().ok()?.to_id_string()),
+        _ => None,
+    }
+}
+
+/// Decodes a value of one of the ASN.1 character string types.
+pub(crate) fn decode_string(value: &Any) -> Option<String> {
+    let data = value.data;
+    match value.tag() {
+        Tag::Utf8String => String::from_utf8(data.to_vec()).ok(),
+        Tag::PrintableString | Tag::Ia5String | Tag::NumericString | Tag::VisibleString => data
+            .is_ascii()
+            .then(|| String::from_utf8_lossy(data).into_owned()),
+        // T.61 is approximated by Latin-1, as most implementations do.
+        Tag::TeletexString => Some(data.iter().map(|&b| b as char).collect()),
+        Tag::BmpString => {
+            if data.len() % 2 != 0 {
+                return None;
+            }
+            let units = data.chunks(2).map(|c| u16::from_be_bytes([c[0], c[1]]));
+            char::decode_utf16(units)
+                .collect::<std::result::Result<String, _>>()
+                .ok()
+        }
+        Tag::UniversalString => {
+            if data.len() % 4 != 0 {
+                return None;
+            }
+            data.chunks(4)
+                .map(|c| char::from_u32(u32::from_be_bytes([c[0], c[1], c[2], c[3]])))
+                .collect()
+        }
+        _ => None,
+    }
+}
+
+/// Escapes special characters as in RFC 4514 Section 2.4.
+fn escape(value: &str) -> String {
+    let last = value.chars().count().saturating_sub(1);
+    let mut escaped = String::with_capacity(value.len());
+    for (i, c) in value.chars().enumerate() {
+        match c {
+            '"' | '+' | ',' | ';' | '<' | '>' | '\\' => escaped.push('\\'),
+            '#' if i == 0 => escaped.push('\\'),
+            ' ' if i == 0 || i == last => escaped.push('\\'),
+            '\0' => {
+                escaped.push_str("\\00");
+                continue;
+            }
+            _ => {}
+        }
+        escaped.push(c);
+    }
+    escaped
+}
+
+#[cfg(test)]
+mod tests {
+    use asn1_rs::FromDer;
+
+    use super::*;
+
+    #[test]
+    fn escape_test() {
+        assert_eq!(escape("a,b+c"), "a\\,b\\+c");
+        assert_eq!(escape(" #a "), "\\ #a\\ ");
+        assert_eq!(escape("#a"), "\\#a");
+        assert_eq!(escape("a\0"), "a\\00");
+    }
+
+    #[test]
+    fn violation_test() {
+        let registry = crate::der::registry::get();
+        // countryName as a UTF8String and as a PrintableString
+        for (der, violation) in [
+            (b"\x30\x09\x06\x03\x55\x04\x06\x0c\x02GB", true),
+            (b"\x30\x09\x06\x03\x55\x04\x06\x13\x02GB", false),
+        ] {
+            let raw = Raw::<AttributeTypeAndValueAsn1>::from_der(der).unwrap().1;
+            let value = AttributeTypeAndValue::from(raw, &registry, der).value;
+            assert_eq!(value.value, "GB");
+            assert_eq!(value.violation.is_some(), violation);
+        }
+    }
+}
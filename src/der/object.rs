@@ -1,30 +1,38 @@
+use std::fmt;
+
 use oid_registry::{Oid, OidEntry};
 use serde::Serialize;
 
 /// Representation of an OID entry.
 pub struct Object {
-    pub oid: String,
+    pub oid: Oid<'static>,
     pub sn: Option<String>,
     pub description: Option<String>,
 }
 
+impl fmt::Display for Object {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if let Some(sn) = &self.sn {
+            write!(f, "{} ({})", sn, self.oid.to_id_string())
+        } else {
+            write!(f, "unknown ({})", self.oid.to_id_string())
+        }
+    }
+}
+
 impl Serialize for Object {
     fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
     where
         S: serde::Serializer,
     {
-        if let Some(sn) = &self.sn {
-            serializer.serialize_str(&format!("{} ({})", sn, self.oid))
-        } else {
-            serializer.serialize_str(&format!("unknown ({})", self.oid))
-        }
+        serializer.collect_str(self)
     }
 }
 
 impl<'a> From<(&'a Oid<'a>, Option<&'a OidEntry>)> for Object {
     fn from((oid, entry): (&Oid, Option<&OidEntry>)) -> Self {
         Object {
-            oid: oid.to_id_string(),
+            oid: oid.to_owned(),
             sn: entry.map(|entry| entry.sn().to_owned()),
             description: entry.map(|entry| entry.description().to_owned()),
         }
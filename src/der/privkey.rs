@@ -1,58 +1,158 @@
 #![allow(non_snake_case)]
-use asn1_rs::{Any, DerSequence, FromDer, Integer, OctetString, Oid};
+use asn1_rs::{Any, Class, DerSequence, Integer, OctetString, Oid};
 use serde::Serialize;
 
-use crate::der::object::Object;
-use crate::der::pubkey::AlgorithmIdentifierAsn1;
-use crate::der::{ed, registry, rsa};
+use crate::der::algorithm::{AlgorithmIdentifier, AlgorithmIdentifierAsn1};
+use crate::der::attribute::{self, Attribute};
+use crate::der::{self, dsa, ec, ed, pubkey, registry, rsa, Raw};
 use crate::error::{Error, Result};
+use crate::int::DisplayedInt;
+use crate::span::Span;
+use crate::verify::Key;
 
-// RFC 5208
-// https://datatracker.ietf.org/doc/html/rfc5208#section-5
+// RFC 5958, which extends PrivateKeyInfo of RFC 5208 with the public key
+// https://datatracker.ietf.org/doc/html/rfc5958#section-2
+// The optional fields are told apart by their tags, `[0]` for the attributes and `[1]` for the
+// public key, since either may be absent.
 #[derive(DerSequence, Debug)]
-struct PrivateKeyInfoAsn1<'a> {
+struct OneAsymmetricKeyAsn1<'a> {
     version: Integer<'a>,
-    privateKeyAlgorithm: AlgorithmIdentifierAsn1<'a>,
+    privateKeyAlgorithm: Raw<'a, AlgorithmIdentifierAsn1<'a>>,
     privateKey: OctetString<'a>,
-    #[allow(unused)]
     attributes: Option<Any<'a>>,
+    publicKey: Option<Any<'a>>,
+}
+
+/// The decoded privateKey, which holds secrets. It is kept as is rather than serialized while
+/// parsing, so that secrets are displayed according to the policy with which it is serialized.
+#[derive(Serialize)]
+#[serde(untagged)]
+pub enum PrivateKeyValue {
+    Rsa(rsa::PrivateKey),
+    Ed(ed::EdPrivateKey),
+    Dsa(dsa::DsaPrivateKey),
+    Ec(ec::EcPrivateKey),
+    /// The algorithm is not supported.
+    Unknown(&'static str),
 }
 
 #[derive(Serialize)]
 pub struct PrivateKey {
-    pub algorithm: Object,
-    pub private_key: serde_json::Value,
+    /// 0 for PrivateKeyInfo (v1), or 1 for OneAsymmetricKey (v2) with a public key.
+    pub version: DisplayedInt,
+    pub algorithm: AlgorithmIdentifier,
+    pub private_key: PrivateKeyValue,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub attributes: Vec<Attribute>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub public_key: Option<serde_json::Value>,
+    /// Whether `public_key` belongs to the private key, for the algorithms whose public key can
+    /// be derived.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub public_key_matches: Option<bool>,
+    pub span: Span,
+}
+
+/// Returns the contents of the BIT STRING of the public key of OneAsymmetricKey, which is
+/// `[1] IMPLICIT`.
+fn public_key_bits<'a>(value: &Any<'a>, base: &[u8]) -> Result<&'a [u8]> {
+    match value.data.split_first() {
+        Some((0, bits)) if value.class() == Class::ContextSpecific && value.tag().0 == 1 => {
+            Ok(bits)
+        }
+        _ => Err(Error::InvalidInputError {
+            field: "publicKey",
+            span: der::any_span(base, value),
+        }),
+    }
+}
+
+/// Returns the private value of the private key `content` from which public keys are derived:
+/// the seed of EdDSA keys, the scalar of EC keys or the whole RSAPrivateKey of RSA keys.
+fn private_value(algorithm: &Oid, content: &[u8]) -> Option<Vec<u8>> {
+    if *algorithm == oid_registry::OID_SIG_ED25519 || *algorithm == oid_registry::OID_SIG_ED448 {
+        let seed: OctetString = der::parse_der(content, "CurvePrivateKey").ok()?;
+        Some(seed.as_cow().to_vec())
+    } else if *algorithm == oid_registry::OID_KEY_TYPE_EC_PUBLIC_KEY {
+        ec::privkey::scalar(content)
+    } else if *algorithm == oid_registry::OID_PKCS1_RSAENCRYPTION
+        || *algorithm == oid_registry::OID_PKCS1_RSASSAPSS
+    {
+        Some(content.to_vec())
+    } else {
+        None
+    }
 }
 
 pub fn parse_private_key(content: &[u8]) -> Result<PrivateKey> {
     let registry = registry::get();
     let x25519 = Oid::from(&[1, 3, 101, 110]).unwrap();
     let x448 = Oid::from(&[1, 3, 101, 111]).unwrap();
-    let (content, key) = PrivateKeyInfoAsn1::from_der(content).unwrap();
-    if !content.is_empty() {
-        return Err(Error::ParseError);
-    }
-    if key.version.as_i32() != Ok(0) {
-        return Err(Error::InvalidInputError);
+    let key: OneAsymmetricKeyAsn1 = der::parse_der(content, "PrivateKeyInfo")?;
+    let (attributes, public_key) = match (&key.attributes, &key.publicKey) {
+        (Some(value), None) if value.tag().0 == 1 => (None, Some(value)),
+        (attributes, public_key) => (attributes.as_ref(), public_key.as_ref()),
+    };
+    // The public key requires v2 (RFC 5958 Section 2).
+    let version = key.version.as_i32();
+    if !(version == Ok(0) && public_key.is_none() || version == Ok(1)) {
+        return Err(Error::InvalidInputError {
+            field: "version",
+            span: der::span_in(content, key.version.any().data),
+        });
     }
-    let algorithm = &key.privateKeyAlgorithm.algorithm;
+    let key_offset = der::span_in(content, key.privateKey.as_cow()).start;
+    let algorithm = &key.privateKeyAlgorithm.value.algorithm;
+    let public_key = public_key
+        .map(|value| public_key_bits(value, content))
+        .transpose()?;
+    let public_key_matches = public_key.and_then(|bits| {
+        let public = Key::from_algorithm(&key.privateKeyAlgorithm.value, bits)?;
+        public.matches_private_key(&private_value(algorithm, key.privateKey.as_cow())?)
+    });
     let mut wrapped = PrivateKey {
-        algorithm: (algorithm, registry.get(algorithm)).into(),
-        private_key: serde_json::Value::String("unknown algorithm".to_string()),
+        version: der::displayed_int(content, &key.version),
+        algorithm: key.privateKeyAlgorithm.decode(&registry, content)?,
+        private_key: PrivateKeyValue::Unknown("unknown algorithm"),
+        attributes: attributes
+            .map(|value| attribute::parse_attributes(value, &registry, content))
+            .transpose()?
+            .unwrap_or_default(),
+        public_key: public_key
+            .map(|bits| pubkey::decode_key(&key.privateKeyAlgorithm.value, bits, content))
+            .transpose()?,
+        public_key_matches,
+        span: Span::new(0, content.len()),
     };
     if *algorithm == oid_registry::OID_PKCS1_RSAENCRYPTION
         || *algorithm == oid_registry::OID_PKCS1_RSASSAPSS
     {
-        let key = rsa::privkey::parse(key.privateKey.as_cow())?;
-        wrapped.private_key = serde_json::to_value(key)?;
+        let key = rsa::privkey::parse_in(key.privateKey.as_cow(), content)
+            .map_err(|e| e.shift(key_offset))?;
+        wrapped.private_key = PrivateKeyValue::Rsa(key);
     }
     if *algorithm == oid_registry::OID_SIG_ED25519
         || *algorithm == oid_registry::OID_SIG_ED448
         || *algorithm == x25519
         || *algorithm == x448
     {
-        let key = ed::privkey::parse(key.privateKey.as_cow())?;
-        wrapped.private_key = serde_json::to_value(key)?;
+        let key = ed::privkey::parse_in(key.privateKey.as_cow(), content)
+            .map_err(|e| e.shift(key_offset))?;
+        wrapped.private_key = PrivateKeyValue::Ed(key);
+    }
+    if *algorithm == dsa::OID_DSA {
+        let parameters =
+            dsa::parameters(key.privateKeyAlgorithm.value.parameter.as_ref(), content)?;
+        let key = dsa::privkey::parse_in(key.privateKey.as_cow(), content, parameters)
+            .map_err(|e| e.shift(key_offset))?;
+        wrapped.private_key = PrivateKeyValue::Dsa(key);
+    }
+    if *algorithm == oid_registry::OID_KEY_TYPE_EC_PUBLIC_KEY {
+        let curve = ec::named_curve(key.privateKeyAlgorithm.value.parameter.as_ref());
+        let key =
+            ec::privkey::parse_in(key.privateKey.as_cow(), content, &registry, curve.as_ref())
+                .map_err(|e| e.shift(key_offset))?;
+        wrapped.private_key = PrivateKeyValue::Ec(key);
     }
     Ok(wrapped)
 }
@@ -1,72 +1,92 @@
 #![allow(non_snake_case)]
-use asn1_rs::{Any, BitString, DerSequence, FromDer, Oid};
+use asn1_rs::{BitString, DerSequence, Oid};
 use oid_registry::OidRegistry;
 use serde::Serialize;
 
-use crate::der::object::Object;
-use crate::der::{ed, registry, rsa};
+use crate::der::algorithm::{AlgorithmIdentifier, AlgorithmIdentifierAsn1};
+use crate::der::{self, algorithm, dsa, ec, ed, registry, rsa, Raw};
 use crate::error::Result;
-
-// RFC 2459
-// https://datatracker.ietf.org/doc/html/rfc2459#section-4.1.1.2
-#[derive(DerSequence, Debug)]
-pub(crate) struct AlgorithmIdentifierAsn1<'a> {
-    pub(crate) algorithm: Oid<'a>,
-    #[allow(unused)] // we haven't seen algorithms that use parameter
-    parameter: Option<Any<'a>>,
-}
-
-impl AlgorithmIdentifierAsn1<'_> {
-    pub(crate) fn to(&self, registry: &OidRegistry) -> Object {
-        let algo = &self.algorithm;
-        (algo, registry.get(algo)).into()
-    }
-}
+use crate::fingerprint;
+use crate::span::Span;
 
 // RFC 2459
 // https://datatracker.ietf.org/doc/html/rfc2459#section-4.1
 #[derive(DerSequence, Debug)]
 pub(crate) struct SubjectPublicKeyInfoAsn1<'a> {
-    algorithm: AlgorithmIdentifierAsn1<'a>,
+    pub(crate) algorithm: Raw<'a, AlgorithmIdentifierAsn1<'a>>,
     pub(crate) subjectPublicKey: BitString<'a>,
 }
 
 impl SubjectPublicKeyInfoAsn1<'_> {
-    pub(crate) fn to(&self, registry: &OidRegistry) -> Result<PublicKey> {
-        let x25519 = Oid::from(&[1, 3, 101, 110]).unwrap();
-        let x448 = Oid::from(&[1, 3, 101, 111]).unwrap();
-        let value = self;
-        let algorithm = &value.algorithm.algorithm;
-        let mut wrapped = PublicKey {
-            algorithm: value.algorithm.to(registry),
-            public_key: serde_json::Value::String("unknown algorithm".to_string()),
-        };
-        if *algorithm == oid_registry::OID_PKCS1_RSAENCRYPTION
-            || *algorithm == oid_registry::OID_PKCS1_RSASSAPSS
-        {
-            let key = rsa::pubkey::parse(&value.subjectPublicKey.data)?;
-            wrapped.public_key = serde_json::to_value(key)?;
-        }
-        if *algorithm == oid_registry::OID_SIG_ED25519
-            || *algorithm == oid_registry::OID_SIG_ED448
-            || *algorithm == x25519
-            || *algorithm == x448
-        {
-            let key = ed::pubkey::parse(&value.subjectPublicKey.data)?;
-            wrapped.public_key = serde_json::to_value(key)?;
-        }
-        Ok(wrapped)
+    /// `base` is the DER from which `self` was parsed. It is used to locate errors.
+    /// `der` is the encoding of `self`, which is fingerprinted.
+    pub(crate) fn to(&self, registry: &OidRegistry, base: &[u8], der: &[u8]) -> Result<PublicKey> {
+        Ok(PublicKey {
+            algorithm: self.algorithm.decode(registry, base)?,
+            public_key: decode_key(&self.algorithm.value, &self.subjectPublicKey.data, base)?,
+            pin_sha256: fingerprint::pin_sha256(der),
+            der: der.to_vec(),
+            span: der::span_in(base, der),
+        })
+    }
+}
+
+/// Decodes the public key `key` of the algorithm `algorithm`, as encoded in the
+/// subjectPublicKey of a SubjectPublicKeyInfo. `key` is a subslice of `base` to which the spans
+/// refer.
+pub(crate) fn decode_key(
+    algorithm: &AlgorithmIdentifierAsn1,
+    key: &[u8],
+    base: &[u8],
+) -> Result<serde_json::Value> {
+    let x25519 = Oid::from(&[1, 3, 101, 110]).unwrap();
+    let x448 = Oid::from(&[1, 3, 101, 111]).unwrap();
+    let parameter = algorithm.parameter.as_ref();
+    let algorithm = &algorithm.algorithm;
+    let key_offset = der::span_in(base, key).start;
+    if *algorithm == oid_registry::OID_PKCS1_RSAENCRYPTION
+        || *algorithm == oid_registry::OID_PKCS1_RSASSAPSS
+        || *algorithm == algorithm::OID_RSAES_OAEP
+    {
+        let key = rsa::pubkey::parse_in(key, base).map_err(|e| e.shift(key_offset))?;
+        return Ok(serde_json::to_value(key)?);
+    }
+    if *algorithm == oid_registry::OID_SIG_ED25519
+        || *algorithm == oid_registry::OID_SIG_ED448
+        || *algorithm == x25519
+        || *algorithm == x448
+    {
+        return Ok(serde_json::to_value(ed::pubkey::parse_in(key, base))?);
+    }
+    if *algorithm == dsa::OID_DSA {
+        let parameters = dsa::parameters(parameter, base)?;
+        let key = dsa::pubkey::parse_in(key, base, parameters).map_err(|e| e.shift(key_offset))?;
+        return Ok(serde_json::to_value(key)?);
+    }
+    if *algorithm == oid_registry::OID_KEY_TYPE_EC_PUBLIC_KEY {
+        let curve = ec::named_curve(parameter);
+        let key =
+            ec::pubkey::parse_in(key, base, curve.as_ref()).map_err(|e| e.shift(key_offset))?;
+        return Ok(serde_json::to_value(key)?);
     }
+    Ok(serde_json::Value::String("unknown algorithm".to_string()))
 }
 
 #[derive(Serialize)]
 pub struct PublicKey {
-    pub algorithm: Object,
+    pub algorithm: AlgorithmIdentifier,
     pub public_key: serde_json::Value,
+    /// SHA-256 of the whole SubjectPublicKeyInfo, as used for key pinning.
+    #[serde(rename = "pin-sha256")]
+    pub pin_sha256: String,
+    /// DER of the whole SubjectPublicKeyInfo.
+    #[serde(skip)]
+    pub der: Vec<u8>,
+    pub span: Span,
 }
 
 pub fn parse_public_key(content: &[u8]) -> Result<PublicKey> {
     let registry = registry::get();
-    let (_rem, value) = SubjectPublicKeyInfoAsn1::from_der(content).unwrap();
-    value.to(&registry)
+    let value: Raw<SubjectPublicKeyInfoAsn1> = der::parse_der(content, "SubjectPublicKeyInfo")?;
+    value.value.to(&registry, content, value.der)
 }
@@ -21,6 +21,99 @@ pub fn get() -> OidRegistry<'static> {
         ),
     );
 
+    // PKCS #1 and SHA-2 entries missing from the crypto feature
+    registry.insert(
+        oid!(1.2.840 .113549 .1 .1 .7),
+        OidEntry::new("rsaesOaep", "RSAES-OAEP encryption scheme"),
+    );
+    registry.insert(
+        oid!(1.2.840 .113549 .1 .1 .8),
+        OidEntry::new("mgf1", "Mask generation function 1"),
+    );
+    registry.insert(
+        oid!(1.2.840 .113549 .1 .1 .9),
+        OidEntry::new("pSpecified", "Source of the OAEP label"),
+    );
+    registry.insert(
+        oid!(2.16.840 .1 .101 .3 .4 .2 .4),
+        OidEntry::new(
+            "sha224",
+            "Secure Hash Algorithm that uses a 224 bit key (SHA224)",
+        ),
+    );
+
+    // Password-based encryption of PKCS #5 and RFC 7914, which the kdf feature lacks
+    registry.insert(
+        oid!(1.2.840 .113549 .1 .5 .13),
+        OidEntry::new("pbes2", "PKCS #5 password-based encryption scheme 2"),
+    );
+    registry.insert(
+        oid!(1.2.840 .113549 .1 .5 .12),
+        OidEntry::new("pbkdf2", "PKCS #5 password-based key derivation function 2"),
+    );
+    registry.insert(
+        oid!(1.3.6 .1 .4 .1 .11591 .4 .11),
+        OidEntry::new("scrypt", "scrypt key derivation function"),
+    );
+    registry.insert(
+        oid!(1.2.840 .113549 .2 .7),
+        OidEntry::new("hmacWithSHA1", "HMAC with SHA-1"),
+    );
+    registry.insert(
+        oid!(1.2.840 .113549 .2 .8),
+        OidEntry::new("hmacWithSHA224", "HMAC with SHA-224"),
+    );
+    registry.insert(
+        oid!(1.2.840 .113549 .2 .9),
+        OidEntry::new("hmacWithSHA256", "HMAC with SHA-256"),
+    );
+    registry.insert(
+        oid!(1.2.840 .113549 .2 .10),
+        OidEntry::new("hmacWithSHA384", "HMAC with SHA-384"),
+    );
+    registry.insert(
+        oid!(1.2.840 .113549 .2 .11),
+        OidEntry::new("hmacWithSHA512", "HMAC with SHA-512"),
+    );
+    registry.insert(
+        oid!(2.16.840 .1 .101 .3 .4 .1 .2),
+        OidEntry::new("aes-128-cbc", "128-bit AES in CBC mode"),
+    );
+    registry.insert(
+        oid!(2.16.840 .1 .101 .3 .4 .1 .22),
+        OidEntry::new("aes-192-cbc", "192-bit AES in CBC mode"),
+    );
+    registry.insert(
+        oid!(2.16.840 .1 .101 .3 .4 .1 .6),
+        OidEntry::new("aes-128-gcm", "128-bit AES in GCM mode"),
+    );
+    registry.insert(
+        oid!(2.16.840 .1 .101 .3 .4 .1 .26),
+        OidEntry::new("aes-192-gcm", "192-bit AES in GCM mode"),
+    );
+    registry.insert(
+        oid!(2.16.840 .1 .101 .3 .4 .1 .46),
+        OidEntry::new("aes-256-gcm", "256-bit AES in GCM mode"),
+    );
+    registry.insert(
+        oid!(1.2.840 .113549 .3 .7),
+        OidEntry::new("des-ede3-cbc", "Triple DES in CBC mode"),
+    );
+
+    // DSA, which the crypto feature lacks
+    registry.insert(
+        oid!(1.2.840 .10040 .4 .1),
+        OidEntry::new("dsa", "Digital Signature Algorithm (DSA)"),
+    );
+    registry.insert(
+        oid!(1.2.840 .10040 .4 .3),
+        OidEntry::new("dsa-with-sha1", "DSA with SHA1"),
+    );
+    registry.insert(
+        oid!(2.16.840 .1 .101 .3 .4 .3 .2),
+        OidEntry::new("dsa-with-sha256", "DSA with SHA256"),
+    );
+
     registry.insert(oid!(2.5.4), OidEntry::new("x509", "X.509"));
     registry.insert(
         oid!(2.5.4 .0),
@@ -101,6 +194,100 @@ pub fn get() -> OidRegistry<'static> {
         OidEntry::new("dnQualifier", "DN Qualifier"),
     );
 
+    // X.509 v3 extensions (RFC 5280) and the OIDs used in their values
+    registry.insert(
+        oid!(2.5.29 .14),
+        OidEntry::new("subjectKeyIdentifier", "X509v3 Subject Key Identifier"),
+    );
+    registry.insert(
+        oid!(2.5.29 .15),
+        OidEntry::new("keyUsage", "X509v3 Key Usage"),
+    );
+    registry.insert(
+        oid!(2.5.29 .17),
+        OidEntry::new("subjectAltName", "X509v3 Subject Alternative Name"),
+    );
+    registry.insert(
+        oid!(2.5.29 .18),
+        OidEntry::new("issuerAltName", "X509v3 Issuer Alternative Name"),
+    );
+    registry.insert(
+        oid!(2.5.29 .19),
+        OidEntry::new("basicConstraints", "X509v3 Basic Constraints"),
+    );
+    registry.insert(
+        oid!(2.5.29 .30),
+        OidEntry::new("nameConstraints", "X509v3 Name Constraints"),
+    );
+    registry.insert(
+        oid!(2.5.29 .31),
+        OidEntry::new("cRLDistributionPoints", "X509v3 CRL Distribution Points"),
+    );
+    registry.insert(
+        oid!(2.5.29 .32),
+        OidEntry::new("certificatePolicies", "X509v3 Certificate Policies"),
+    );
+    registry.insert(
+        oid!(2.5.29 .32 .0),
+        OidEntry::new("anyPolicy", "X509v3 Any Policy"),
+    );
+    registry.insert(
+        oid!(2.5.29 .35),
+        OidEntry::new("authorityKeyIdentifier", "X509v3 Authority Key Identifier"),
+    );
+    registry.insert(
+        oid!(2.5.29 .37),
+        OidEntry::new("extendedKeyUsage", "X509v3 Extended Key Usage"),
+    );
+    registry.insert(
+        oid!(2.5.29 .37 .0),
+        OidEntry::new("anyExtendedKeyUsage", "Any Extended Key Usage"),
+    );
+    registry.insert(
+        oid!(1.3.6 .1 .5 .5 .7 .1 .1),
+        OidEntry::new("authorityInfoAccess", "Authority Information Access"),
+    );
+    registry.insert(
+        oid!(1.3.6 .1 .5 .5 .7 .2 .1),
+        OidEntry::new("cps", "Policy Qualifier CPS"),
+    );
+    registry.insert(
+        oid!(1.3.6 .1 .5 .5 .7 .2 .2),
+        OidEntry::new("unotice", "Policy Qualifier User Notice"),
+    );
+    registry.insert(
+        oid!(1.3.6 .1 .5 .5 .7 .3 .1),
+        OidEntry::new("serverAuth", "TLS Web Server Authentication"),
+    );
+    registry.insert(
+        oid!(1.3.6 .1 .5 .5 .7 .3 .2),
+        OidEntry::new("clientAuth", "TLS Web Client Authentication"),
+    );
+    registry.insert(
+        oid!(1.3.6 .1 .5 .5 .7 .3 .3),
+        OidEntry::new("codeSigning", "Code Signing"),
+    );
+    registry.insert(
+        oid!(1.3.6 .1 .5 .5 .7 .3 .4),
+        OidEntry::new("emailProtection", "E-mail Protection"),
+    );
+    registry.insert(
+        oid!(1.3.6 .1 .5 .5 .7 .3 .8),
+        OidEntry::new("timeStamping", "Time Stamping"),
+    );
+    registry.insert(
+        oid!(1.3.6 .1 .5 .5 .7 .3 .9),
+        OidEntry::new("OCSPSigning", "OCSP Signing"),
+    );
+    registry.insert(
+        oid!(1.3.6 .1 .5 .5 .7 .48 .1),
+        OidEntry::new("ocsp", "OCSP"),
+    );
+    registry.insert(
+        oid!(1.3.6 .1 .5 .5 .7 .48 .2),
+        OidEntry::new("caIssuers", "CA Issuers"),
+    );
+
     // Additional entries that are missing in oid_registry
     registry.insert(
         oid!(1.3.101 .110),
@@ -117,5 +304,11 @@ pub fn get() -> OidRegistry<'static> {
         ),
     );
 
+    // PKCS #9 attribute missing from the pkcs9 feature
+    registry.insert(
+        oid!(1.2.840 .113549 .1 .9 .21),
+        OidEntry::new("localKeyID", "PKCS #9 attribute localKeyID (for PKCS #12)"),
+    );
+
     registry
 }
@@ -1,21 +1,26 @@
-use asn1_rs::{DerSequence, FromDer, Integer};
+use asn1_rs::{DerSequence, Integer};
 use serde::Serialize;
 
+use crate::consistency::Check;
+use crate::der::{self, Raw};
 use crate::error::{Error, Result};
-use crate::int::{DisplayedInt, PrivateInt};
+use crate::int::DisplayedInt;
+use crate::secret::Secret;
 use crate::span::Span;
 
 #[derive(Serialize)]
 pub struct PublicKey {
     pub modulus: DisplayedInt,
     pub exponent: DisplayedInt,
+    pub span: Span,
 }
 
 #[derive(Serialize)]
 pub struct OtherPrime {
-    pub prime: PrivateInt,
-    pub exponent: PrivateInt,
-    pub coefficient: PrivateInt,
+    pub prime: Secret,
+    pub exponent: Secret,
+    pub coefficient: Secret,
+    pub span: Span,
 }
 
 #[derive(Serialize)]
@@ -24,26 +29,30 @@ pub struct PrivateKey {
     #[serde(rename = "publicExponent")]
     pub public_exponent: DisplayedInt,
     #[serde(rename = "privateExponent")]
-    pub private_exponent: PrivateInt,
-    pub prime1: PrivateInt,
-    pub prime2: PrivateInt,
-    pub exponent1: PrivateInt,
-    pub exponent2: PrivateInt,
-    pub coefficient: PrivateInt,
+    pub private_exponent: Secret,
+    pub prime1: Secret,
+    pub prime2: Secret,
+    pub exponent1: Secret,
+    pub exponent2: Secret,
+    pub coefficient: Secret,
     #[serde(skip_serializing_if = "Vec::is_empty", rename = "otherPrimeInfos")]
     pub other_primes: Vec<OtherPrime>,
+    /// Consistency checks of the components.
+    pub checks: Vec<Check>,
+    pub span: Span,
 }
 
 #[allow(non_snake_case)]
 pub mod privkey {
     use asn1_rs::{Any, SequenceOf};
+    use num_bigint::BigUint;
 
     use super::*;
+    use crate::consistency::RsaComponents;
 
     // RFC 8017
     // https://datatracker.ietf.org/doc/html/rfc8017#appendix-A.1.2
     #[derive(DerSequence, Debug)]
-    #[allow(unused)]
     struct OtherPrimeInfoAsn1<'a> {
         prime: Integer<'a>,
         exponent: Integer<'a>,
@@ -64,39 +73,89 @@ pub mod privkey {
         otherPrimeInfos: Option<Any<'a>>, // OPTIONAL
     }
 
+    fn secret(base: &[u8], value: &Integer) -> Secret {
+        Secret::integer(&value.as_bigint(), der::int_span(base, value))
+    }
+
+    /// Negative values, which are invalid in every component, are checked as zero.
+    fn biguint(value: &Integer) -> BigUint {
+        value.as_biguint().unwrap_or_default()
+    }
+
     pub fn parse(content: &[u8]) -> Result<PrivateKey> {
-        let (_rem, value) = RsaPrivateKeyAsn1::from_der(content).map_err(asn1_rs::Error::from)?;
+        parse_in(content, content)
+    }
+
+    /// Parses `content`, a subslice of `base` to which the spans refer.
+    pub(crate) fn parse_in(content: &[u8], base: &[u8]) -> Result<PrivateKey> {
+        let value: RsaPrivateKeyAsn1 = der::parse_der(content, "RSAPrivateKey")?;
         let other_primes = if value.version.as_i32() == Ok(0) {
-            if value.otherPrimeInfos.is_some() {
-                return Err(Error::InvalidInputError);
+            if let Some(info) = value.otherPrimeInfos {
+                return Err(Error::InvalidInputError {
+                    field: "otherPrimeInfos",
+                    span: der::span_in(content, info.data),
+                });
             }
             Vec::new()
         } else {
             let other_primes = if let Some(info) = value.otherPrimeInfos {
                 info
             } else {
-                return Err(Error::InvalidInputError);
+                return Err(Error::InvalidInputError {
+                    field: "otherPrimeInfos",
+                    span: Span::new(content.len(), content.len()),
+                });
             };
-            let other_primes = SequenceOf::<OtherPrimeInfoAsn1>::try_from(other_primes)?;
+            let span = der::span_in(content, other_primes.data);
+            let other_primes = SequenceOf::<Raw<OtherPrimeInfoAsn1>>::try_from(other_primes)
+                .map_err(Error::asn1("otherPrimeInfos", span))?;
             other_primes
                 .iter()
-                .map(|info| OtherPrime {
-                    prime: (&info.prime).into(),
-                    exponent: (&info.exponent).into(),
-                    coefficient: (&info.coefficient).into(),
+                .map(|info| {
+                    let prime = OtherPrime {
+                        prime: secret(base, &info.value.prime),
+                        exponent: secret(base, &info.value.exponent),
+                        coefficient: secret(base, &info.value.coefficient),
+                        span: info.span(base),
+                    };
+                    let components = [
+                        biguint(&info.value.prime),
+                        biguint(&info.value.exponent),
+                        biguint(&info.value.coefficient),
+                    ];
+                    (prime, components)
                 })
                 .collect()
         };
+        let mut components = RsaComponents {
+            n: biguint(&value.modulus),
+            e: biguint(&value.publicExponent),
+            d: biguint(&value.privateExponent),
+            primes: vec![biguint(&value.prime1), biguint(&value.prime2)],
+            exponents: vec![biguint(&value.exponent1), biguint(&value.exponent2)],
+            coefficients: vec![biguint(&value.coefficient)],
+        };
+        let other_primes = other_primes
+            .into_iter()
+            .map(|(prime, [r, exponent, coefficient])| {
+                components.primes.push(r);
+                components.exponents.push(exponent);
+                components.coefficients.push(coefficient);
+                prime
+            })
+            .collect();
         Ok(PrivateKey {
-            modulus: DisplayedInt::new(value.modulus.as_bigint(), Span::new(0, 0)), // TODO: span
-            public_exponent: DisplayedInt::new(value.publicExponent.as_bigint(), Span::new(0, 0)), // TODO: span
-            private_exponent: value.privateExponent.into(),
-            prime1: value.prime1.into(),
-            prime2: value.prime2.into(),
-            exponent1: value.exponent1.into(),
-            exponent2: value.exponent2.into(),
-            coefficient: value.coefficient.into(),
+            modulus: der::displayed_int(base, &value.modulus),
+            public_exponent: der::displayed_int(base, &value.publicExponent),
+            private_exponent: secret(base, &value.privateExponent),
+            prime1: secret(base, &value.prime1),
+            prime2: secret(base, &value.prime2),
+            exponent1: secret(base, &value.exponent1),
+            exponent2: secret(base, &value.exponent2),
+            coefficient: secret(base, &value.coefficient),
             other_primes,
+            checks: components.check(),
+            span: der::span_in(base, content),
         })
     }
 }
@@ -113,10 +172,33 @@ pub mod pubkey {
     }
 
     pub fn parse(content: &[u8]) -> Result<PublicKey> {
-        let (_, key) = RsaPublicKeyAsn1::from_der(content).unwrap();
+        parse_in(content, content)
+    }
+
+    /// Parses `content`, a subslice of `base` to which the spans refer.
+    pub(crate) fn parse_in(content: &[u8], base: &[u8]) -> Result<PublicKey> {
+        let key: RsaPublicKeyAsn1 = der::parse_der(content, "RSAPublicKey")?;
         Ok(PublicKey {
-            modulus: DisplayedInt::new(key.modulus.as_bigint(), Span::new(0, 0)), // TODO: span
-            exponent: DisplayedInt::new(key.exponent.as_bigint(), Span::new(0, 0)), // TODO: span
+            modulus: der::displayed_int(base, &key.modulus),
+            exponent: der::displayed_int(base, &key.exponent),
+            span: der::span_in(base, content),
         })
     }
+    /// Returns whether `content` is exactly a SEQUENCE of two INTEGERs, whose modulus is a
+    /// positive odd integer larger than the exponent.
+    ///
+    /// DER input without a label is only taken as an RSAPublicKey if this holds, because many
+    /// other structures, such as DSA private keys, begin with two INTEGERs.
+    pub(crate) fn is_plausible(content: &[u8]) -> bool {
+        let Ok(elements) = der::parse_der::<Vec<Integer>>(content, "RSAPublicKey") else {
+            return false;
+        };
+        let [modulus, exponent] = elements.as_slice() else {
+            return false;
+        };
+        match (modulus.as_biguint(), exponent.as_biguint()) {
+            (Ok(modulus), Ok(exponent)) => modulus.bit(0) && modulus > exponent,
+            _ => false,
+        }
+    }
 }
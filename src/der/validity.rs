@@ -0,0 +1,107 @@
+//! Validity periods of certificates.
+#![allow(non_snake_case)]
+use asn1_rs::{ASN1DateTime, ASN1TimeZone, Any, DerSequence, GeneralizedTime, Tag, UtcTime};
+use serde::Serialize;
+
+use crate::der::{self, Raw};
+use crate::error::{Error, Result};
+use crate::span::Span;
+use crate::time;
+pub use crate::time::Status;
+
+// https://datatracker.ietf.org/doc/html/rfc5280#section-4.1.2.5
+#[derive(DerSequence)]
+pub(crate) struct ValidityAsn1<'a> {
+    notBefore: Any<'a>,
+    notAfter: Any<'a>,
+}
+
+/// A point in time, displayed in ISO-8601.
+#[derive(Serialize)]
+pub struct Time {
+    pub value: String,
+    /// UNIX timestamp.
+    #[serde(skip)]
+    pub timestamp: i64,
+    pub span: Span,
+}
+
+impl Time {
+    /// Decodes a UTCTime or GeneralizedTime.
+    fn from(value: &Any, base: &[u8], field: &'static str) -> Result<Self> {
+        let span = der::any_span(base, value);
+        let invalid = || Error::InvalidInputError { field, span };
+        let (datetime, utc) = match value.tag() {
+            Tag::UtcTime => (UtcTime::try_from(value).map_err(|_| invalid())?.0, true),
+            Tag::GeneralizedTime => (
+                GeneralizedTime::try_from(value).map_err(|_| invalid())?.0,
+                false,
+            ),
+            _ => return Err(invalid()),
+        };
+        let timestamp = timestamp(&datetime, utc).ok_or_else(invalid)?;
+        Ok(Self {
+            value: time::to_iso8601(timestamp),
+            timestamp,
+            span,
+        })
+    }
+}
+
+/// Converts `datetime` to a UNIX timestamp. UTCTime has two-digit years, which are interpreted as
+/// in RFC 5280 Section 4.1.2.5.1.
+fn timestamp(datetime: &ASN1DateTime, utc: bool) -> Option<i64> {
+    let mut year = i64::from(datetime.year);
+    if utc {
+        year += if year >= 50 { 1900 } else { 2000 };
+    }
+    if !(1..=12).contains(&datetime.month) || !(1..=31).contains(&datetime.day) {
+        return None;
+    }
+    let days = time::days_from_civil(year, datetime.month.into(), datetime.day.into());
+    let secs = i64::from(datetime.hour) * 3600
+        + i64::from(datetime.minute) * 60
+        + i64::from(datetime.second);
+    let offset = match datetime.tz {
+        ASN1TimeZone::Undefined | ASN1TimeZone::Z => 0,
+        ASN1TimeZone::Offset(hours, minutes) => {
+            let minutes = if hours < 0 { -minutes } else { minutes };
+            i64::from(hours) * 3600 + i64::from(minutes) * 60
+        }
+    };
+    Some(days * 86400 + secs - offset)
+}
+
+#[derive(Serialize)]
+pub struct Validity {
+    #[serde(rename = "notBefore")]
+    pub not_before: Time,
+    #[serde(rename = "notAfter")]
+    pub not_after: Time,
+    pub span: Span,
+}
+
+impl Validity {
+    pub(crate) fn from(value: &Raw<ValidityAsn1>, base: &[u8]) -> Result<Self> {
+        Ok(Self {
+            not_before: Time::from(&value.value.notBefore, base, "notBefore")?,
+            not_after: Time::from(&value.value.notAfter, base, "notAfter")?,
+            span: value.span(base),
+        })
+    }
+
+    /// Returns the status at the UNIX timestamp `at`. Certificates expiring within
+    /// `expires_within` seconds after `at` are reported as [`Status::ExpiringSoon`].
+    pub fn status(&self, at: i64, expires_within: i64) -> Status {
+        // Both ends are inclusive (RFC 5280 Section 4.1.2.5).
+        if at < self.not_before.timestamp {
+            Status::NotYetValid
+        } else if at > self.not_after.timestamp {
+            Status::Expired
+        } else if at.saturating_add(expires_within) > self.not_after.timestamp {
+            Status::ExpiringSoon
+        } else {
+            Status::Valid
+        }
+    }
+}
@@ -0,0 +1,455 @@
+//! Detection of input formats.
+//!
+//! [`parse`] detects the container of the input (an OpenSSH public key line, PEM blocks, raw DER
+//! or an `openssh-key-v1` blob) and dispatches its content to the right parser.
+use serde::Serialize;
+
+#[cfg(feature = "der")]
+use crate::der::{cert, csr, dsa, ec, encrypted, privkey, pubkey, rsa};
+use crate::error::{Error, Result};
+#[cfg(feature = "openssh")]
+use crate::openssh;
+use crate::span::Span;
+
+/// Container in which a document was found.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Container {
+    /// A single line in `authorized_keys` format.
+    OpensshPublicKey,
+    /// A PEM block with the given tag.
+    Pem { tag: String },
+    /// Raw DER.
+    Der,
+    /// A raw `openssh-key-v1` blob.
+    OpensshKeyV1,
+    /// The plaintext of the preceding encrypted private key.
+    Decrypted,
+}
+
+/// A parsed document.
+#[derive(Serialize)]
+#[serde(untagged)]
+pub enum Value {
+    #[cfg(feature = "der")]
+    PublicKey(pubkey::PublicKey),
+    #[cfg(feature = "der")]
+    RsaPublicKey(rsa::PublicKey),
+    #[cfg(feature = "der")]
+    RsaPrivateKey(rsa::PrivateKey),
+    #[cfg(feature = "der")]
+    PrivateKey(privkey::PrivateKey),
+    #[cfg(feature = "der")]
+    EcPrivateKey(ec::EcPrivateKey),
+    #[cfg(feature = "der")]
+    DsaPrivateKey(dsa::DsaPrivateKey),
+    #[cfg(feature = "der")]
+    EncryptedPrivateKey(encrypted::EncryptedPrivateKey),
+    #[cfg(feature = "der")]
+    EncryptedPemKey(encrypted::legacy::EncryptedPemKey),
+    #[cfg(feature = "der")]
+    Certificate(Box<cert::Certificate>),
+    #[cfg(feature = "der")]
+    CertificationRequest(Box<csr::CertificationRequest>),
+    #[cfg(feature = "openssh")]
+    OpensshPublicKey(openssh::pubkey::PublicKey),
+    #[cfg(feature = "openssh")]
+    OpensshPrivateKey(openssh::privkey::PrivateKey),
+}
+
+/// A header of a PEM block (RFC 1421 Section 4.6), e.g. `Proc-Type`.
+#[derive(Serialize)]
+pub struct Header {
+    pub name: String,
+    pub value: String,
+}
+
+#[derive(Serialize)]
+pub struct Document {
+    /// Human-readable description of the document, e.g. `PEM certificate`.
+    #[serde(rename = "type")]
+    pub ty: &'static str,
+    /// Headers of the PEM block, which most blocks do not have.
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub headers: Vec<Header>,
+    pub value: Value,
+    #[serde(skip)]
+    pub container: Container,
+    /// Location of the document in the input.
+    #[serde(skip)]
+    pub span: Span,
+    /// The decoded binary, to which the spans in `value` refer.
+    #[serde(skip)]
+    pub data: Vec<u8>,
+}
+
+const PEM_BEGIN: &[u8] = b"-----BEGIN ";
+const PEM_END: &[u8] = b"-----END ";
+const PEM_DASHES: &[u8] = b"-----";
+#[cfg(feature = "openssh")]
+const OPENSSH_KEY_V1: &[u8] = b"openssh-key-v1\0";
+
+/// Options of [`parse_with`].
+#[derive(Debug, Clone, Default)]
+pub struct ParseOptions {
+    /// The passphrase with which encrypted private keys are decrypted.
+    pub passphrase: Option<Vec<u8>>,
+}
+
+/// Parses all documents in `input` with the default options.
+pub fn parse(input: &[u8]) -> Result<Vec<Document>> {
+    parse_with(input, &ParseOptions::default())
+}
+
+/// Parses all documents in `input`.
+///
+/// PEM blocks with unsupported tags are skipped. Encrypted private keys are followed by their
+/// decryption if `options` has a passphrase.
+/// If no documents are found, [`Error::UnsupportedFormat`] is returned.
+#[cfg_attr(not(feature = "der"), allow(unused_variables))]
+pub fn parse_with(input: &[u8], options: &ParseOptions) -> Result<Vec<Document>> {
+    #[cfg(feature = "openssh")]
+    if let Ok(text) = std::str::from_utf8(input) {
+        if let Ok(value) = openssh::pubkey::parse(text) {
+            return Ok(vec![Document {
+                ty: "OPENSSH public key",
+                headers: vec![],
+                data: value.blob.clone(),
+                value: Value::OpensshPublicKey(value),
+                container: Container::OpensshPublicKey,
+                span: Span::new(0, input.len()),
+            }]);
+        }
+    }
+    let blocks = find_pem_blocks(input);
+    let documents = if blocks.is_empty() {
+        let mut documents = vec![];
+        if let Some(document) = parse_binary(input)? {
+            #[cfg(feature = "der")]
+            let decrypted = decrypt(&document, options.passphrase.as_deref())?;
+            documents.push(document);
+            #[cfg(feature = "der")]
+            documents.extend(decrypted);
+        }
+        documents
+    } else {
+        let mut documents = vec![];
+        for span in blocks {
+            let block = &input[span.start..span.end];
+            let wrap = |source| Error::PemBlockError {
+                tag: pem_label(block),
+                span,
+                source: Box::new(source),
+            };
+            let pem = pem::parse(block).map_err(|e| wrap(e.into()))?;
+            if let Some(document) = parse_pem(pem, span).map_err(wrap)? {
+                // A wrong passphrase is not reported as a malformed block.
+                #[cfg(feature = "der")]
+                let decrypted =
+                    decrypt(&document, options.passphrase.as_deref()).map_err(|e| match e {
+                        Error::WrongPassphrase => e,
+                        e => wrap(e),
+                    })?;
+                documents.push(document);
+                #[cfg(feature = "der")]
+                documents.extend(decrypted);
+            }
+        }
+        documents
+    };
+    if documents.is_empty() {
+        return Err(Error::UnsupportedFormat);
+    }
+    Ok(documents)
+}
+
+/// Finds the locations of PEM blocks in `input`.
+fn find_pem_blocks(input: &[u8]) -> Vec<Span> {
+    let find = |from: usize, needle: &[u8]| {
+        input[from..]
+            .windows(needle.len())
+            .position(|window| window == needle)
+            .map(|pos| from + pos)
+    };
+    let mut blocks = vec![];
+    let mut pos = 0;
+    while let Some(start) = find(pos, PEM_BEGIN) {
+        let Some(end_line) = find(start + PEM_BEGIN.len(), PEM_END) else {
+            break;
+        };
+        let Some(end) = find(end_line + PEM_END.len(), PEM_DASHES) else {
+            break;
+        };
+        let end = end + PEM_DASHES.len();
+        blocks.push(Span::new(start, end));
+        pos = end;
+    }
+    blocks
+}
+
+/// Extracts the label from the first line of a PEM block.
+fn pem_label(block: &[u8]) -> String {
+    let label = &block[PEM_BEGIN.len()..];
+    let end = label
+        .windows(PEM_DASHES.len())
+        .position(|window| window == PEM_DASHES)
+        .unwrap_or(0);
+    String::from_utf8_lossy(&label[..end]).into_owned()
+}
+
+fn parse_pem(pem: pem::Pem, span: Span) -> Result<Option<Document>> {
+    let tag = pem.tag().to_owned();
+    let headers = pem
+        .headers()
+        .iter()
+        .map(|(name, value)| Header {
+            name: name.to_owned(),
+            value: value.to_owned(),
+        })
+        .collect();
+    #[cfg(feature = "der")]
+    if let Some(ty) = encrypted_pem_type(&tag) {
+        if let Some(key) = encrypted::legacy::parse(pem.headers(), pem.contents())? {
+            return Ok(Some(Document {
+                ty,
+                headers,
+                value: Value::EncryptedPemKey(key),
+                container: Container::Pem { tag },
+                span,
+                data: pem.into_contents(),
+            }));
+        }
+    }
+    let Some((ty, value)) = parse_pem_content(&tag, pem.contents())? else {
+        return Ok(None);
+    };
+    Ok(Some(Document {
+        ty,
+        headers,
+        value,
+        container: Container::Pem { tag },
+        span,
+        data: pem.into_contents(),
+    }))
+}
+
+/// Returns the type of traditional private keys with the tag `tag` when they are encrypted with
+/// the `Proc-Type` and `DEK-Info` headers.
+#[cfg(feature = "der")]
+fn encrypted_pem_type(tag: &str) -> Option<&'static str> {
+    match tag {
+        "RSA PRIVATE KEY" => Some("PEM encrypted RSA private key"),
+        "EC PRIVATE KEY" => Some("PEM encrypted EC private key"),
+        "DSA PRIVATE KEY" => Some("PEM encrypted DSA private key"),
+        _ => None,
+    }
+}
+
+/// Parses the content of a PEM block with the tag `tag`. Returns `None` for unsupported tags.
+fn parse_pem_content(tag: &str, contents: &[u8]) -> Result<Option<(&'static str, Value)>> {
+    let (ty, value) = match tag {
+        #[cfg(feature = "der")]
+        "PUBLIC KEY" => (
+            "PEM public key",
+            Value::PublicKey(pubkey::parse_public_key(contents)?),
+        ),
+        #[cfg(feature = "der")]
+        "RSA PUBLIC KEY" => (
+            "PEM RSA public key",
+            Value::RsaPublicKey(rsa::pubkey::parse(contents)?),
+        ),
+        #[cfg(feature = "der")]
+        "RSA PRIVATE KEY" => (
+            "PEM RSA private key",
+            Value::RsaPrivateKey(rsa::privkey::parse(contents)?),
+        ),
+        #[cfg(feature = "der")]
+        "EC PRIVATE KEY" => (
+            "PEM EC private key",
+            Value::EcPrivateKey(ec::privkey::parse(contents)?),
+        ),
+        #[cfg(feature = "der")]
+        "DSA PRIVATE KEY" => (
+            "PEM DSA private key",
+            Value::DsaPrivateKey(dsa::privkey::parse(contents)?),
+        ),
+        #[cfg(feature = "der")]
+        "PRIVATE KEY" => (
+            "PEM private key",
+            Value::PrivateKey(privkey::parse_private_key(contents)?),
+        ),
+        #[cfg(feature = "der")]
+        "ENCRYPTED PRIVATE KEY" => (
+            "PEM encrypted private key",
+            Value::EncryptedPrivateKey(encrypted::parse(contents)?),
+        ),
+        #[cfg(feature = "der")]
+        "CERTIFICATE" => (
+            "PEM certificate",
+            Value::Certificate(Box::new(cert::parse(contents)?)),
+        ),
+        #[cfg(feature = "der")]
+        "CERTIFICATE REQUEST" => (
+            "PEM certificate request",
+            Value::CertificationRequest(Box::new(csr::parse_csr(contents)?)),
+        ),
+        #[cfg(feature = "openssh")]
+        "OPENSSH PRIVATE KEY" => (
+            "OPENSSH private key",
+            Value::OpensshPrivateKey(openssh::privkey::parse(contents, 0)?),
+        ),
+        _ => return Ok(None),
+    };
+    Ok(Some((ty, value)))
+}
+
+/// Decrypts `document` with `passphrase` if it is an encrypted private key and there is a
+/// passphrase. The decrypted key is a document of its own, whose spans refer to the plaintext.
+#[cfg(feature = "der")]
+fn decrypt(document: &Document, passphrase: Option<&[u8]>) -> Result<Option<Document>> {
+    let Some(passphrase) = passphrase else {
+        return Ok(None);
+    };
+    let (plaintext, value) = match (&document.value, &document.container) {
+        (Value::EncryptedPrivateKey(_), _) => {
+            let plaintext = encrypted::decrypt(&document.data, passphrase)?;
+            let value = Value::PrivateKey(privkey::parse_private_key(&plaintext)?);
+            (plaintext, value)
+        }
+        (Value::EncryptedPemKey(key), Container::Pem { tag }) => {
+            let plaintext = encrypted::legacy::decrypt(key, &document.data, passphrase)?;
+            let Some((_, value)) = parse_pem_content(tag, &plaintext)? else {
+                return Ok(None);
+            };
+            (plaintext, value)
+        }
+        _ => return Ok(None),
+    };
+    Ok(Some(Document {
+        ty: "decrypted private key",
+        headers: vec![],
+        value,
+        container: Container::Decrypted,
+        span: document.span,
+        data: plaintext,
+    }))
+}
+
+/// Parses `input` as an `openssh-key-v1` blob or as one of the DER structures.
+fn parse_binary(input: &[u8]) -> Result<Option<Document>> {
+    let document = |ty, value, container| Document {
+        ty,
+        headers: vec![],
+        value,
+        container,
+        span: Span::new(0, input.len()),
+        data: input.to_vec(),
+    };
+    #[cfg(feature = "openssh")]
+    if input.starts_with(OPENSSH_KEY_V1) {
+        let value = openssh::privkey::parse(input, 0)?;
+        return Ok(Some(document(
+            "OPENSSH private key",
+            Value::OpensshPrivateKey(value),
+            Container::OpensshKeyV1,
+        )));
+    }
+    #[cfg(feature = "der")]
+    {
+        if let Ok(value) = cert::parse(input) {
+            let value = Value::Certificate(Box::new(value));
+            return Ok(Some(document("DER certificate", value, Container::Der)));
+        }
+        if let Ok(value) = csr::parse_csr(input) {
+            let value = Value::CertificationRequest(Box::new(value));
+            return Ok(Some(document(
+                "DER certificate request",
+                value,
+                Container::Der,
+            )));
+        }
+        if let Ok(value) = pubkey::parse_public_key(input) {
+            let value = Value::PublicKey(value);
+            return Ok(Some(document("DER public key", value, Container::Der)));
+        }
+        if let Ok(value) = privkey::parse_private_key(input) {
+            let value = Value::PrivateKey(value);
+            return Ok(Some(document("DER private key", value, Container::Der)));
+        }
+        if let Ok(value) = rsa::privkey::parse(input) {
+            let value = Value::RsaPrivateKey(value);
+            return Ok(Some(document("DER RSA private key", value, Container::Der)));
+        }
+        if let Ok(value) = ec::privkey::parse(input) {
+            let value = Value::EcPrivateKey(value);
+            return Ok(Some(document("DER EC private key", value, Container::Der)));
+        }
+        if let Ok(value) = dsa::privkey::parse(input) {
+            let value = Value::DsaPrivateKey(value);
+            return Ok(Some(document("DER DSA private key", value, Container::Der)));
+        }
+        if let Ok(value) = encrypted::parse(input) {
+            let value = Value::EncryptedPrivateKey(value);
+            return Ok(Some(document(
+                "DER encrypted private key",
+                value,
+                Container::Der,
+            )));
+        }
+        // Tried last, and only if the input looks like nothing but an RSAPublicKey.
+        if rsa::pubkey::is_plausible(input) {
+            let value = Value::RsaPublicKey(rsa::pubkey::parse(input)?);
+            return Ok(Some(document("DER RSA public key", value, Container::Der)));
+        }
+        // Broken DER is reported as such rather than as an unsupported format.
+        if input.first() == Some(&0x30) {
+            if let Some(span) = crate::der::find_malformed(input) {
+                return Err(Error::ParseError { field: "DER", span });
+            }
+        }
+    }
+    Ok(None)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn find_pem_blocks_test() {
+        let input = b"junk\n-----BEGIN A-----\nAAAA\n-----END A-----\n\n-----BEGIN B-----\nAAAA\n-----END B-----\n";
+        let blocks = find_pem_blocks(input);
+        assert_eq!(blocks, vec![Span::new(5, 43), Span::new(45, 83)]);
+        assert!(input[blocks[1].start..blocks[1].end].starts_with(b"-----BEGIN B"));
+        assert!(input[blocks[1].start..blocks[1].end].ends_with(b"-----END B-----"));
+    }
+
+    #[test]
+    fn parse_test_negative() {
+        assert!(matches!(parse(b"not a key"), Err(Error::UnsupportedFormat)));
+    }
+
+    #[cfg(feature = "der")]
+    #[test]
+    fn parse_binary_test_rsa_public_key() {
+        let ty = |input: &[u8]| {
+            parse_binary(input)
+                .ok()
+                .flatten()
+                .map(|document| document.ty)
+        };
+        // modulus 3233 and exponent 17
+        let key = [0x30, 0x07, 0x02, 0x02, 0x0c, 0xa1, 0x02, 0x01, 0x11];
+        assert_eq!(ty(&key), Some("DER RSA public key"));
+        // A third INTEGER, as in DSA private keys
+        let three = [
+            0x30, 0x0a, 0x02, 0x02, 0x0c, 0xa1, 0x02, 0x01, 0x11, 0x02, 0x01, 0x01,
+        ];
+        assert_eq!(ty(&three), None);
+        // An even modulus, and a modulus smaller than the exponent
+        assert_eq!(
+            ty(&[0x30, 0x07, 0x02, 0x02, 0x0c, 0xa0, 0x02, 0x01, 0x11]),
+            None
+        );
+        assert_eq!(ty(&[0x30, 0x06, 0x02, 0x01, 0x0b, 0x02, 0x01, 0x11]), None);
+    }
+}
@@ -1,3 +1,5 @@
+use crate::span::Span;
+
 #[derive(Debug, thiserror::Error)]
 #[non_exhaustive]
 pub enum Error {
@@ -7,16 +9,90 @@ pub enum Error {
         #[source]
         serde_json::Error,
     ),
-    #[error("Input is invalid")]
-    InvalidInputError,
-    #[error("Parsing failed")]
-    ParseError,
-    #[error("ASN.1 parsing failed")]
-    Asn1Error(
+    #[error("`{field}` at bytes {span} is invalid")]
+    InvalidInputError { field: &'static str, span: Span },
+    #[error("Parsing `{field}` at bytes {span} failed")]
+    ParseError { field: &'static str, span: Span },
+    #[error("ASN.1 parsing of `{field}` at bytes {span} failed")]
+    Asn1Error {
+        field: &'static str,
+        span: Span,
+        #[source]
+        source: asn1_rs::Error,
+    },
+    #[error("PEM parsing failed")]
+    PemError(
+        #[from]
+        #[source]
+        pem::PemError,
+    ),
+    #[cfg(feature = "openssh")]
+    #[error("OpenSSH key parsing failed")]
+    OpensshError(
         #[from]
         #[source]
-        asn1_rs::Error,
+        crate::openssh::error::Error,
     ),
+    #[error("PEM block `{tag}` at bytes {span} is malformed")]
+    PemBlockError {
+        tag: String,
+        span: Span,
+        #[source]
+        source: Box<Error>,
+    },
+    #[error("`{field}` at bytes {span} uses an unsupported algorithm")]
+    UnsupportedAlgorithm { field: &'static str, span: Span },
+    #[error("PEM header `{name}` is invalid")]
+    PemHeaderError { name: &'static str },
+    #[error("Unsupported cipher `{0}`")]
+    UnsupportedCipher(String),
+    #[error("Decryption failed: wrong passphrase")]
+    WrongPassphrase,
+    #[error("Unsupported format")]
+    UnsupportedFormat,
+}
+
+impl Error {
+    /// Returns a function that wraps an ASN.1 error with the location of `field`.
+    #[cfg(feature = "der")]
+    pub(crate) fn asn1(field: &'static str, span: Span) -> impl FnOnce(asn1_rs::Error) -> Self {
+        move |source| Error::Asn1Error {
+            field,
+            span,
+            source,
+        }
+    }
+
+    /// Moves the location of the error by `offset` bytes.
+    ///
+    /// This is used when the error happened in data nested at `offset`.
+    #[cfg(feature = "der")]
+    pub(crate) fn shift(mut self, offset: usize) -> Self {
+        match &mut self {
+            Error::InvalidInputError { span, .. }
+            | Error::ParseError { span, .. }
+            | Error::UnsupportedAlgorithm { span, .. }
+            | Error::Asn1Error { span, .. } => span.shift(offset),
+            _ => {}
+        }
+        self
+    }
+
+    /// Returns the name and the location of the field that could not be parsed, if known.
+    ///
+    /// For errors in PEM blocks, the location is relative to the decoded content of the block.
+    pub fn location(&self) -> Option<(&'static str, Span)> {
+        match self {
+            Error::InvalidInputError { field, span }
+            | Error::ParseError { field, span }
+            | Error::UnsupportedAlgorithm { field, span }
+            | Error::Asn1Error { field, span, .. } => Some((field, *span)),
+            #[cfg(feature = "openssh")]
+            Error::OpensshError(e) => e.location(),
+            Error::PemBlockError { source, .. } => source.location(),
+            _ => None,
+        }
+    }
 }
 
 pub type Result<T> = std::result::Result<T, Error>;
@@ -0,0 +1,91 @@
+//! Fingerprints of keys and certificates, in the formats of the tools that display them, so that
+//! they can be compared with their output.
+use base64::Engine;
+use md5::Md5;
+use serde::Serialize;
+use sha1::Sha1;
+use sha2::{Digest, Sha256};
+
+/// Fingerprints of an OpenSSH public key blob, as displayed by `ssh-keygen -l`.
+#[derive(Serialize)]
+pub struct OpensshFingerprint {
+    /// `SHA256:` followed by unpadded base64.
+    pub sha256: String,
+    /// `MD5:` followed by colon-separated hex.
+    pub md5: String,
+}
+
+impl OpensshFingerprint {
+    pub fn new(blob: &[u8]) -> Self {
+        Self {
+            sha256: format!(
+                "SHA256:{}",
+                base64::prelude::BASE64_STANDARD_NO_PAD.encode(Sha256::digest(blob))
+            ),
+            md5: format!("MD5:{}", colon_hex(&Md5::digest(blob), false)),
+        }
+    }
+}
+
+/// Thumbprints of a DER certificate, as displayed by `openssl x509 -fingerprint`.
+#[derive(Serialize)]
+pub struct Thumbprint {
+    pub sha1: String,
+    pub sha256: String,
+}
+
+impl Thumbprint {
+    pub fn new(der: &[u8]) -> Self {
+        Self {
+            sha1: colon_hex(&Sha1::digest(der), true),
+            sha256: colon_hex(&Sha256::digest(der), true),
+        }
+    }
+}
+
+/// SHA-256 of a DER SubjectPublicKeyInfo in base64, as used by HTTP public key pinning
+/// ([RFC 7469](https://datatracker.ietf.org/doc/html/rfc7469#section-2.4)).
+pub fn pin_sha256(spki: &[u8]) -> String {
+    base64::prelude::BASE64_STANDARD.encode(Sha256::digest(spki))
+}
+
+/// Formats `bytes` as lowercase hex without separators.
+pub(crate) fn hex(bytes: &[u8]) -> String {
+    bytes.iter().map(|b| format!("{:02x}", b)).collect()
+}
+
+pub(crate) fn colon_hex(bytes: &[u8], upper: bool) -> String {
+    bytes
+        .iter()
+        .map(|b| {
+            if upper {
+                format!("{:02X}", b)
+            } else {
+                format!("{:02x}", b)
+            }
+        })
+        .collect::<Vec<_>>()
+        .join(":")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn openssh_fingerprint_test() {
+        // ssh-keygen -l -E sha256 / -E md5 on the key in `openssh::pubkey` tests
+        let blob = base64::prelude::BASE64_STANDARD
+            .decode("AAAAC3NzaC1lZDI1NTE5AAAAIF/cpeiuO8aMA4abrDs87slcIRMK/AhG3WNVKg7X48Aj")
+            .unwrap();
+        let fingerprint = OpensshFingerprint::new(&blob);
+        assert_eq!(
+            fingerprint.sha256,
+            "SHA256:/wuOEHu38U5IxYv7XXS9bV+LKvodhWo7vFJVZV/UAqw"
+        );
+        assert_eq!(
+            fingerprint.md5,
+            "MD5:a4:84:60:97:f2:44:86:6b:29:5a:d5:ef:01:89:2d:ec"
+        );
+    }
+}
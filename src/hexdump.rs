@@ -0,0 +1,181 @@
+//! Annotated hex dump of decoded binary data.
+//!
+//! Every node of a displayed value that carries a `span` is shown next to the bytes it covers,
+//! similarly to `dumpasn1` or the byte pane of Wireshark.
+use std::cmp::Reverse;
+use std::fmt::Write;
+
+use serde_json::Value;
+
+const BYTES_PER_ROW: usize = 16;
+const INDENT: &str = "  ";
+
+struct Entry {
+    name: String,
+    start: usize,
+    end: usize,
+}
+
+/// Renders `data` as a hex dump annotated with the fields of `value`.
+///
+/// The spans in `value` must be offsets into `data`.
+pub fn render(data: &[u8], value: &Value) -> String {
+    let mut entries = vec![];
+    collect(value, "", &mut entries);
+    entries.retain(|entry| entry.start < entry.end && entry.end <= data.len());
+    // Sorting is stable, so a parent precedes its children even if their spans are equal.
+    entries.sort_by_key(|entry| (entry.start, Reverse(entry.end)));
+    let mut out = String::new();
+    render_range(&mut out, data, &entries, 0, data.len(), "", 0);
+    out
+}
+
+fn collect(value: &Value, path: &str, out: &mut Vec<Entry>) {
+    let join = |key: &str| {
+        if path.is_empty() {
+            key.to_string()
+        } else {
+            format!("{}.{}", path, key)
+        }
+    };
+    match value {
+        Value::Object(map) => {
+            if let Some((start, end)) = map.get("span").and_then(span) {
+                out.push(Entry {
+                    // The root is the value of the document.
+                    name: if path.is_empty() { "value" } else { path }.to_string(),
+                    start,
+                    end,
+                });
+            }
+            for (key, value) in map {
+                if key != "span" {
+                    collect(value, &join(key), out);
+                }
+            }
+        }
+        Value::Array(array) => {
+            for (i, value) in array.iter().enumerate() {
+                collect(value, &join(&i.to_string()), out);
+            }
+        }
+        _ => {}
+    }
+}
+
+fn span(value: &Value) -> Option<(usize, usize)> {
+    let map = value.as_object()?;
+    let start = map.get("start")?.as_u64()?;
+    let end = map.get("end")?.as_u64()?;
+    Some((start as usize, end as usize))
+}
+
+/// Renders `data[start..end]`. `entries` are the sorted entries within that range.
+fn render_range(
+    out: &mut String,
+    data: &[u8],
+    entries: &[Entry],
+    start: usize,
+    end: usize,
+    parent: &str,
+    depth: usize,
+) {
+    let mut pos = start;
+    let mut i = 0;
+    while i < entries.len() {
+        let entry = &entries[i];
+        let mut j = i + 1;
+        while j < entries.len() && entries[j].start < entry.end && entries[j].end <= entry.end {
+            j += 1;
+        }
+        if pos < entry.start {
+            render_rows(out, data, pos, entry.start, depth);
+        }
+        let name = entry
+            .name
+            .strip_prefix(parent)
+            .and_then(|name| name.strip_prefix('.'))
+            .unwrap_or(&entry.name);
+        writeln!(
+            out,
+            "{:08x}  {}{} ({} bytes)",
+            entry.start,
+            INDENT.repeat(depth),
+            name,
+            entry.end - entry.start,
+        )
+        .unwrap();
+        if j == i + 1 {
+            render_rows(out, data, entry.start, entry.end, depth + 1);
+        } else {
+            let children = &entries[i + 1..j];
+            render_range(
+                out,
+                data,
+                children,
+                entry.start,
+                entry.end,
+                &entry.name,
+                depth + 1,
+            );
+        }
+        pos = pos.max(entry.end);
+        i = j;
+    }
+    if pos < end {
+        render_rows(out, data, pos, end, depth);
+    }
+}
+
+fn render_rows(out: &mut String, data: &[u8], start: usize, end: usize, depth: usize) {
+    for (i, row) in data[start..end].chunks(BYTES_PER_ROW).enumerate() {
+        let hex: Vec<_> = row.iter().map(|b| format!("{:02x}", b)).collect();
+        let ascii: String = row
+            .iter()
+            .map(|&b| {
+                if b.is_ascii_graphic() || b == b' ' {
+                    b as char
+                } else {
+                    '.'
+                }
+            })
+            .collect();
+        writeln!(
+            out,
+            "{:08x}  {}{:<width$}  |{}|",
+            start + i * BYTES_PER_ROW,
+            INDENT.repeat(depth),
+            hex.join(" "),
+            ascii,
+            width = BYTES_PER_ROW * 3 - 1,
+        )
+        .unwrap();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn render_test_nested() {
+        let data = b"\x00\x00\x00\x01a\x00\x00\x00\x02bc!";
+        let value = json!({
+            "outer": {
+                "first": { "value": "a", "span": { "start": 0, "end": 5 } },
+                "second": { "value": "bc", "span": { "start": 5, "end": 11 } },
+                "span": { "start": 0, "end": 11 },
+            },
+        });
+        let expected = "\
+00000000  outer (11 bytes)
+00000000    first (5 bytes)
+00000000      00 00 00 01 61                                   |....a|
+00000005    second (6 bytes)
+00000005      00 00 00 02 62 63                                |....bc|
+0000000b  21                                               |!|
+";
+        assert_eq!(render(data, &value), expected);
+    }
+}
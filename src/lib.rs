@@ -1,8 +1,22 @@
+#[cfg(any(feature = "der", feature = "openssh"))]
+pub mod consistency;
 #[cfg(feature = "der")]
 pub mod der;
+pub mod document;
 pub mod error;
+pub mod fingerprint;
+pub mod hexdump;
 pub mod int;
+#[cfg(feature = "der")]
+pub mod lint;
 #[cfg(feature = "openssh")]
 pub mod openssh;
+pub mod secret;
 pub mod span;
 pub mod string;
+pub mod text;
+pub mod time;
+#[cfg(feature = "der")]
+pub mod verify;
+
+pub use document::{parse, parse_with};
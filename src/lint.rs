@@ -0,0 +1,841 @@
+//! Checks of certificates against [RFC 5280](https://datatracker.ietf.org/doc/html/rfc5280) and
+//! the CA/Browser Forum Baseline Requirements for TLS server certificates (BR).
+//!
+//! Each check is identified by a short name and reports findings with a severity. Violations of
+//! MUST requirements are errors, and those of SHOULD requirements are warnings.
+use asn1_rs::{oid, Any, FromDer, Integer, Oid, Sequence};
+use serde::Serialize;
+
+use crate::der::algorithm::Parameters;
+use crate::der::cert::Certificate;
+use crate::der::extension::{Extension, ExtensionValue, KeyUsage};
+use crate::der::pubkey::SubjectPublicKeyInfoAsn1;
+use crate::span::Span;
+use crate::time;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum Severity {
+    Warning,
+    Error,
+}
+
+#[derive(Serialize)]
+pub struct Finding {
+    pub lint: &'static str,
+    pub severity: Severity,
+    pub message: String,
+    /// The requirement that is violated.
+    pub reference: &'static str,
+    /// Location of the offending field, if it is present in the certificate.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub span: Option<Span>,
+}
+
+const BASIC_CONSTRAINTS: Oid<'static> = oid!(2.5.29 .19);
+const KEY_USAGE: Oid<'static> = oid!(2.5.29 .15);
+const EXTENDED_KEY_USAGE: Oid<'static> = oid!(2.5.29 .37);
+const SUBJECT_ALT_NAME: Oid<'static> = oid!(2.5.29 .17);
+const SUBJECT_KEY_IDENTIFIER: Oid<'static> = oid!(2.5.29 .14);
+const AUTHORITY_KEY_IDENTIFIER: Oid<'static> = oid!(2.5.29 .35);
+const SERVER_AUTH: Oid<'static> = oid!(1.3.6 .1 .5 .5 .7 .3 .1);
+
+const RSA: Oid<'static> = oid!(1.2.840 .113549 .1 .1 .1);
+const RSAES_OAEP: Oid<'static> = oid!(1.2.840 .113549 .1 .1 .7);
+const RSASSA_PSS: Oid<'static> = oid!(1.2.840 .113549 .1 .1 .10);
+const EC_PUBLIC_KEY: Oid<'static> = oid!(1.2.840 .10045 .2 .1);
+const X25519: Oid<'static> = oid!(1.3.101 .110);
+const X448: Oid<'static> = oid!(1.3.101 .111);
+const ED25519: Oid<'static> = oid!(1.3.101 .112);
+const ED448: Oid<'static> = oid!(1.3.101 .113);
+
+/// Maximum validity in days of TLS server certificates issued on or after each date (BR Section
+/// 6.3.2). The later limits were set by Ballot SC-081.
+const MAX_TLS_VALIDITY_DAYS: [((i64, u32, u32), i64); 5] = [
+    ((2018, 3, 1), 825),
+    ((2020, 9, 1), 398),
+    ((2026, 3, 15), 200),
+    ((2027, 3, 15), 100),
+    ((2029, 3, 15), 47),
+];
+
+/// Returns the maximum validity of a TLS server certificate whose notBefore is `not_before`, and
+/// the date from which it applies, or `None` if the certificate predates all limits.
+fn max_tls_validity_days(not_before: i64) -> Option<((i64, u32, u32), i64)> {
+    MAX_TLS_VALIDITY_DAYS
+        .iter()
+        .rev()
+        .find(|&&((year, month, day), _)| {
+            time::days_from_civil(year, month, day) * 86400 <= not_before
+        })
+        .copied()
+}
+
+struct Linter<'a> {
+    cert: &'a Certificate,
+    /// The DER of the certificate, to which the spans in `cert` refer.
+    der: &'a [u8],
+    findings: Vec<Finding>,
+}
+
+impl<'a> Linter<'a> {
+    fn report(
+        &mut self,
+        lint: &'static str,
+        severity: Severity,
+        message: impl Into<String>,
+        reference: &'static str,
+        span: Option<Span>,
+    ) {
+        self.findings.push(Finding {
+            lint,
+            severity,
+            message: message.into(),
+            reference,
+            span,
+        });
+    }
+
+    fn bytes(&self, span: Span) -> &'a [u8] {
+        &self.der[span.start..span.end]
+    }
+
+    fn extensions(&self) -> &'a [Extension] {
+        self.cert
+            .tbs_certificate
+            .extensions
+            .as_deref()
+            .unwrap_or_default()
+    }
+
+    fn extension(&self, oid: &Oid) -> Option<&'a Extension> {
+        self.extensions()
+            .iter()
+            .find(|ext| ext.extn_id.value.oid == *oid)
+    }
+
+    fn key_algorithm(&self) -> &'a Oid<'static> {
+        &self
+            .cert
+            .tbs_certificate
+            .subject_pki
+            .algorithm
+            .algorithm
+            .oid
+    }
+
+    fn is_ca(&self) -> bool {
+        matches!(
+            self.extension(&BASIC_CONSTRAINTS)
+                .map(|ext| &ext.extn_value.value),
+            Some(ExtensionValue::BasicConstraints { cA: true, .. })
+        )
+    }
+
+    fn key_usage(&self) -> Option<KeyUsage> {
+        match self.extension(&KEY_USAGE)?.extn_value.value {
+            ExtensionValue::KeyUsage(bits) => Some(bits),
+            _ => None,
+        }
+    }
+
+    /// Whether the certificate is a TLS server certificate of an end entity, to which the
+    /// Baseline Requirements apply.
+    fn is_tls_server_leaf(&self) -> bool {
+        let server_auth = match self
+            .extension(&EXTENDED_KEY_USAGE)
+            .map(|ext| &ext.extn_value.value)
+        {
+            Some(ExtensionValue::Objects(purposes)) => {
+                purposes.iter().any(|purpose| purpose.oid == SERVER_AUTH)
+            }
+            _ => false,
+        };
+        server_auth && !self.is_ca()
+    }
+
+    // RFC 5280 Section 4.1.1.2
+    fn signature_algorithm(&mut self) {
+        let inner = self.cert.tbs_certificate.signature.span;
+        let outer = self.cert.signature_algorithm.span;
+        if let (Some(inner), Some(outer)) = (inner, outer) {
+            if self.bytes(inner) != self.bytes(outer) {
+                self.report(
+                    "signature-algorithm-mismatch",
+                    Severity::Error,
+                    "tbsCertificate.signature differs from signatureAlgorithm",
+                    "RFC 5280 Section 4.1.1.2",
+                    Some(inner),
+                );
+            }
+        }
+        let algorithm = &self.cert.signature_algorithm;
+        let oid = &algorithm.algorithm.oid;
+        let span = algorithm.span;
+        let pkcs1 = [
+            oid!(1.2.840 .113549 .1 .1 .2),
+            oid!(1.2.840 .113549 .1 .1 .3),
+            oid!(1.2.840 .113549 .1 .1 .4),
+            oid!(1.2.840 .113549 .1 .1 .5),
+            oid!(1.2.840 .113549 .1 .1 .11),
+            oid!(1.2.840 .113549 .1 .1 .12),
+            oid!(1.2.840 .113549 .1 .1 .13),
+            oid!(1.2.840 .113549 .1 .1 .14),
+        ];
+        let ecdsa = [
+            oid!(1.2.840 .10045 .4 .1),
+            oid!(1.2.840 .10045 .4 .3 .1),
+            oid!(1.2.840 .10045 .4 .3 .2),
+            oid!(1.2.840 .10045 .4 .3 .3),
+            oid!(1.2.840 .10045 .4 .3 .4),
+        ];
+        let null = matches!(algorithm.parameters, Parameters::Null(_));
+        let absent = matches!(algorithm.parameters, Parameters::Absent);
+        if pkcs1.contains(oid) && !null {
+            self.report(
+                "rsa-signature-parameters-not-null",
+                Severity::Error,
+                "the parameters of PKCS #1 v1.5 signature algorithms must be NULL",
+                "RFC 4055 Section 5",
+                span,
+            );
+        }
+        if (ecdsa.contains(oid) || *oid == ED25519 || *oid == ED448) && !absent {
+            self.report(
+                "signature-parameters-present",
+                Severity::Error,
+                "the parameters of ECDSA and EdDSA signature algorithms must be absent",
+                "RFC 5758 Section 3.2, RFC 8410 Section 3",
+                span,
+            );
+        }
+        let md = [
+            oid!(1.2.840 .113549 .1 .1 .2),
+            oid!(1.2.840 .113549 .1 .1 .3),
+            oid!(1.2.840 .113549 .1 .1 .4),
+        ];
+        let sha1 = [
+            oid!(1.2.840 .113549 .1 .1 .5),
+            oid!(1.2.840 .10045 .4 .1),
+            oid!(1.3.14 .3 .2 .29),
+        ];
+        if md.contains(oid) {
+            self.report(
+                "weak-signature-algorithm",
+                Severity::Error,
+                format!("{} is broken", algorithm.algorithm),
+                "BR Section 7.1.3.2",
+                span,
+            );
+        } else if sha1.contains(oid) {
+            self.report(
+                "weak-signature-algorithm",
+                Severity::Warning,
+                format!(
+                    "{} relies on SHA-1, which is not collision resistant",
+                    algorithm.algorithm
+                ),
+                "BR Section 7.1.3.2",
+                span,
+            );
+        }
+    }
+
+    // RFC 5280 Section 4.1.2.2, BR Section 7.1
+    fn serial_number(&mut self) {
+        let span = self.cert.tbs_certificate.serial_number.span;
+        let Ok((_, any)) = Any::from_der(self.bytes(span)) else {
+            return;
+        };
+        let content = any.data;
+        if content.first().map_or(true, |&b| b & 0x80 != 0) || content.iter().all(|&b| b == 0) {
+            self.report(
+                "serial-number-not-positive",
+                Severity::Error,
+                "the serial number must be a positive integer",
+                "RFC 5280 Section 4.1.2.2",
+                Some(span),
+            );
+        }
+        if content.len() > 20 {
+            self.report(
+                "serial-number-too-long",
+                Severity::Error,
+                format!(
+                    "the serial number has {} octets, but must have at most 20",
+                    content.len()
+                ),
+                "RFC 5280 Section 4.1.2.2",
+                Some(span),
+            );
+        }
+        // The leading zero only makes the integer positive.
+        let significant = content.iter().skip_while(|&&b| b == 0).count();
+        if significant < 8 {
+            self.report(
+                "serial-number-low-entropy",
+                Severity::Warning,
+                "the serial number is shorter than 64 bits, which cannot hold the required \
+                 entropy",
+                "BR Section 7.1",
+                Some(span),
+            );
+        }
+    }
+
+    // RFC 5280 Sections 4.1.2.1, 4.1.2.8 and 4.1.2.9
+    fn version(&mut self) {
+        let tbs = &self.cert.tbs_certificate;
+        let version = &tbs.version;
+        if version.value == "v1" && version.span.is_some() {
+            self.report(
+                "version-default-encoded",
+                Severity::Error,
+                "the DEFAULT version v1 must be omitted in DER",
+                "X.690 Section 11.5",
+                version.span,
+            );
+        }
+        if tbs.extensions.is_some() && version.value != "v3" {
+            self.report(
+                "extensions-require-v3",
+                Severity::Error,
+                "certificates with extensions must be v3",
+                "RFC 5280 Section 4.1.2.1",
+                version.span,
+            );
+        }
+        let unique_id = tbs
+            .issuer_unique_id
+            .as_ref()
+            .or(tbs.subject_unique_id.as_ref());
+        if let Some(unique_id) = unique_id {
+            if version.value == "v1" {
+                self.report(
+                    "unique-identifier-requires-v2",
+                    Severity::Error,
+                    "unique identifiers must not appear in v1 certificates",
+                    "RFC 5280 Section 4.1.2.8",
+                    Some(unique_id.span),
+                );
+            }
+            self.report(
+                "unique-identifier-present",
+                Severity::Warning,
+                "conforming CAs should not generate unique identifiers",
+                "RFC 5280 Section 4.1.2.8",
+                Some(unique_id.span),
+            );
+        }
+    }
+
+    // RFC 5280 Section 4.1.2.5, BR Section 6.3.2
+    fn validity(&mut self) {
+        let validity = &self.cert.tbs_certificate.validity;
+        let y2050 = time::days_from_civil(2050, 1, 1) * 86400;
+        for time in [&validity.not_before, &validity.not_after] {
+            let utc = self.der[time.span.start] == 0x17;
+            if utc != (time.timestamp < y2050) {
+                self.report(
+                    "validity-time-encoding",
+                    Severity::Error,
+                    "dates before 2050 must be in UTCTime and later dates in GeneralizedTime",
+                    "RFC 5280 Section 4.1.2.5",
+                    Some(time.span),
+                );
+            }
+        }
+        if validity.not_after.timestamp < validity.not_before.timestamp {
+            self.report(
+                "validity-negative",
+                Severity::Error,
+                "notAfter precedes notBefore",
+                "RFC 5280 Section 4.1.2.5",
+                Some(validity.span),
+            );
+            return;
+        }
+        // Both ends are inclusive.
+        let seconds = validity.not_after.timestamp - validity.not_before.timestamp + 1;
+        let Some(((year, month, day), max_days)) =
+            max_tls_validity_days(validity.not_before.timestamp)
+        else {
+            return;
+        };
+        if self.is_tls_server_leaf() && seconds > max_days * 86400 {
+            self.report(
+                "validity-too-long",
+                Severity::Error,
+                format!(
+                    "TLS server certificates issued on or after {:04}-{:02}-{:02} must be valid \
+                     for at most {} days, but this one is valid for {} days",
+                    year,
+                    month,
+                    day,
+                    max_days,
+                    (seconds + 86399) / 86400
+                ),
+                "BR Section 6.3.2",
+                Some(validity.span),
+            );
+        }
+    }
+
+    // RFC 5280 Sections 4.1.2.6, 4.2 and 4.2.1.6, BR Section 7.1.2.7.12
+    fn names(&mut self) {
+        let tbs = &self.cert.tbs_certificate;
+        for name in [&tbs.issuer, &tbs.subject] {
+            for value in name.rdn_sequence.iter().flatten().map(|x| &x.value) {
+                if let Some(violation) = &value.violation {
+                    self.report(
+                        "name-string-type",
+                        Severity::Error,
+                        format!("{}, not {}", violation, value.string_type),
+                        "RFC 5280 Appendix A.1",
+                        Some(value.span),
+                    );
+                }
+            }
+        }
+        let san = self.extension(&SUBJECT_ALT_NAME);
+        if tbs.subject.rdn_sequence.is_empty() {
+            match san {
+                None => self.report(
+                    "subject-empty-without-san",
+                    Severity::Error,
+                    "certificates with an empty subject must have subjectAltName",
+                    "RFC 5280 Section 4.1.2.6",
+                    Some(tbs.subject.span),
+                ),
+                Some(san) if !san.critical => self.report(
+                    "san-not-critical",
+                    Severity::Error,
+                    "subjectAltName must be critical if the subject is empty",
+                    "RFC 5280 Section 4.2.1.6",
+                    Some(san.span),
+                ),
+                _ => {}
+            }
+        }
+        if let Some(san) = san {
+            if matches!(&san.extn_value.value, ExtensionValue::GeneralNames(names) if names.is_empty())
+            {
+                self.report(
+                    "san-empty",
+                    Severity::Error,
+                    "subjectAltName must contain at least one name",
+                    "RFC 5280 Section 4.2.1.6",
+                    Some(san.extn_value.span),
+                );
+            }
+        } else if self.is_tls_server_leaf() {
+            self.report(
+                "tls-server-without-san",
+                Severity::Error,
+                "TLS server certificates must have subjectAltName",
+                "BR Section 7.1.2.7.12",
+                None,
+            );
+        }
+    }
+
+    // RFC 5280 Section 4.2
+    fn duplicate_extensions(&mut self) {
+        let extensions = self.extensions();
+        let duplicates: Vec<Span> = extensions
+            .iter()
+            .enumerate()
+            .filter(|(i, ext)| {
+                extensions[..*i]
+                    .iter()
+                    .any(|prev| prev.extn_id.value.oid == ext.extn_id.value.oid)
+            })
+            .map(|(_, ext)| ext.span)
+            .collect();
+        for span in duplicates {
+            self.report(
+                "extension-duplicated",
+                Severity::Error,
+                "an extension must not appear more than once",
+                "RFC 5280 Section 4.2",
+                Some(span),
+            );
+        }
+    }
+
+    // RFC 5280 Section 4.2
+    fn malformed_extensions(&mut self) {
+        for ext in self.extensions() {
+            if let Some(error) = &ext.error {
+                self.report(
+                    "extension-malformed",
+                    Severity::Error,
+                    format!("the value of {} is malformed: {}", ext.extn_id.value, error),
+                    "RFC 5280 Section 4.2",
+                    Some(ext.extn_value.span),
+                );
+            }
+        }
+    }
+
+    // RFC 5280 Sections 4.2.1.1, 4.2.1.2, 4.2.1.3 and 4.2.1.9
+    fn ca(&mut self) {
+        let is_ca = self.is_ca();
+        let key_cert_sign = self
+            .key_usage()
+            .is_some_and(|bits| bits.intersects(KeyUsage::KEY_CERT_SIGN));
+        if let Some(bc) = self.extension(&BASIC_CONSTRAINTS) {
+            let ExtensionValue::BasicConstraints {
+                pathLenConstraint, ..
+            } = bc.extn_value.value
+            else {
+                return;
+            };
+            let span = Some(bc.span);
+            if is_ca && !bc.critical {
+                self.report(
+                    "ca-basic-constraints-not-critical",
+                    Severity::Error,
+                    "basicConstraints must be critical in CA certificates",
+                    "RFC 5280 Section 4.2.1.9",
+                    span,
+                );
+            }
+            if pathLenConstraint.is_some() && !(is_ca && key_cert_sign) {
+                self.report(
+                    "path-len-without-key-cert-sign",
+                    Severity::Error,
+                    "pathLenConstraint requires cA and the keyCertSign bit",
+                    "RFC 5280 Section 4.2.1.9",
+                    span,
+                );
+            }
+        }
+        if key_cert_sign && !is_ca {
+            self.report(
+                "key-cert-sign-without-ca",
+                Severity::Error,
+                "the keyCertSign bit requires cA in basicConstraints",
+                "RFC 5280 Section 4.2.1.3",
+                self.extension(&KEY_USAGE).map(|ext| ext.span),
+            );
+        }
+        if is_ca && self.extension(&SUBJECT_KEY_IDENTIFIER).is_none() {
+            self.report(
+                "ca-without-subject-key-identifier",
+                Severity::Error,
+                "CA certificates must have subjectKeyIdentifier",
+                "RFC 5280 Section 4.2.1.2",
+                None,
+            );
+        }
+        if !self.cert.self_issued && self.extension(&AUTHORITY_KEY_IDENTIFIER).is_none() {
+            self.report(
+                "missing-authority-key-identifier",
+                Severity::Error,
+                "certificates that are not self-signed must have authorityKeyIdentifier",
+                "RFC 5280 Section 4.2.1.1",
+                None,
+            );
+        }
+    }
+
+    // RFC 5280 Section 4.2.1.3, RFC 4055 Section 1.2, RFC 5480 Section 3, RFC 8410 Section 5
+    fn key_usage_consistency(&mut self) {
+        let Some(ext) = self.extension(&KEY_USAGE) else {
+            return;
+        };
+        let span = Some(ext.span);
+        let critical = ext.critical;
+        let Some(bits) = self.key_usage() else {
+            return;
+        };
+        if bits.is_empty() {
+            self.report(
+                "key-usage-empty",
+                Severity::Error,
+                "keyUsage must have at least one bit set",
+                "RFC 5280 Section 4.2.1.3",
+                span,
+            );
+        }
+        if !critical {
+            self.report(
+                "key-usage-not-critical",
+                Severity::Warning,
+                "keyUsage should be critical",
+                "RFC 5280 Section 4.2.1.3",
+                span,
+            );
+        }
+        if bits.intersects(KeyUsage::ENCIPHER_ONLY | KeyUsage::DECIPHER_ONLY)
+            && !bits.intersects(KeyUsage::KEY_AGREEMENT)
+        {
+            self.report(
+                "key-usage-encipher-decipher-only",
+                Severity::Error,
+                "encipherOnly and decipherOnly are only defined with keyAgreement",
+                "RFC 5280 Section 4.2.1.3",
+                span,
+            );
+        }
+        let signing = KeyUsage::DIGITAL_SIGNATURE
+            | KeyUsage::NON_REPUDIATION
+            | KeyUsage::KEY_CERT_SIGN
+            | KeyUsage::CRL_SIGN;
+        let encryption = KeyUsage::KEY_ENCIPHERMENT | KeyUsage::DATA_ENCIPHERMENT;
+        let agreement = KeyUsage::KEY_AGREEMENT | KeyUsage::ENCIPHER_ONLY | KeyUsage::DECIPHER_ONLY;
+        // The bits that the key type allows, and the specification that restricts them.
+        let key = self.key_algorithm();
+        let (allowed, reference) = if *key == RSA {
+            (signing | encryption, "RFC 3279 Section 2.3.1")
+        } else if *key == RSASSA_PSS {
+            (signing, "RFC 4055 Section 1.2")
+        } else if *key == RSAES_OAEP {
+            (encryption, "RFC 4055 Section 1.2")
+        } else if *key == EC_PUBLIC_KEY {
+            (signing | agreement, "RFC 5480 Section 3")
+        } else if *key == ED25519 || *key == ED448 {
+            (signing, "RFC 8410 Section 5")
+        } else if *key == X25519 || *key == X448 {
+            (agreement, "RFC 8410 Section 5")
+        } else {
+            return;
+        };
+        let disallowed = bits.difference(allowed);
+        if !disallowed.is_empty() {
+            let key = self
+                .cert
+                .tbs_certificate
+                .subject_pki
+                .algorithm
+                .algorithm
+                .to_string();
+            self.report(
+                "key-usage-inconsistent-with-key",
+                Severity::Error,
+                format!(
+                    "not allowed for {} keys: {}",
+                    key,
+                    disallowed.names().join(", ")
+                ),
+                reference,
+                span,
+            );
+        }
+    }
+
+    // BR Section 6.1.5
+    fn key_size(&mut self) {
+        let pki = &self.cert.tbs_certificate.subject_pki;
+        if ![RSA, RSASSA_PSS].contains(self.key_algorithm()) {
+            return;
+        }
+        let Ok((_, spki)) = SubjectPublicKeyInfoAsn1::from_der(&pki.der) else {
+            return;
+        };
+        let Ok((_, key)) = Sequence::from_der(spki.subjectPublicKey.data.as_ref()) else {
+            return;
+        };
+        let Ok((_, modulus)) = Integer::from_der(key.content.as_ref()) else {
+            return;
+        };
+        let bits = modulus.as_biguint().map_or(0, |n| n.bits());
+        if bits < 2048 {
+            self.report(
+                "rsa-key-too-small",
+                Severity::Error,
+                format!(
+                    "the RSA modulus has {} bits, but must have at least 2048",
+                    bits
+                ),
+                "BR Section 6.1.5",
+                Some(pki.span),
+            );
+        } else if bits % 8 != 0 {
+            self.report(
+                "rsa-key-size-not-multiple-of-8",
+                Severity::Error,
+                format!(
+                    "the RSA modulus has {} bits, which is not divisible by 8",
+                    bits
+                ),
+                "BR Section 6.1.5",
+                Some(pki.span),
+            );
+        }
+    }
+
+    // X.690 Section 10
+    fn encoding(&mut self) {
+        let mut spans = vec![];
+        non_minimal(self.der, 0, &mut spans);
+        // Extension values are DER nested in OCTET STRINGs.
+        for ext in self.extensions() {
+            let span = ext.extn_value.span;
+            if let Ok((_, value)) = Any::from_der(self.bytes(span)) {
+                let offset = span.end - value.data.len();
+                non_minimal(value.data, offset, &mut spans);
+            }
+        }
+        for span in spans {
+            self.report(
+                "non-minimal-encoding",
+                Severity::Error,
+                "the value is not in its minimal DER encoding",
+                "X.690 Section 10",
+                Some(span),
+            );
+        }
+        let defaults: Vec<Span> = self
+            .extensions()
+            .iter()
+            .filter(|ext| {
+                // The encoding of critical FALSE after the OID of extnID.
+                let id_end = ext.extn_id.span.end;
+                !ext.critical && self.der[id_end..ext.span.end].starts_with(&[0x01, 0x01, 0x00])
+            })
+            .map(|ext| ext.span)
+            .collect();
+        for span in defaults {
+            self.report(
+                "critical-default-encoded",
+                Severity::Error,
+                "the DEFAULT value FALSE of critical must be omitted in DER",
+                "X.690 Section 11.5",
+                Some(span),
+            );
+        }
+    }
+}
+
+/// Finds the TLVs in `data`, which starts at `offset` in the certificate, whose length or value
+/// is not in its minimal encoding. Constructed values are searched recursively.
+fn non_minimal(data: &[u8], offset: usize, out: &mut Vec<Span>) {
+    let mut pos = 0;
+    while pos < data.len() {
+        let start = pos;
+        let tag = data[pos];
+        pos += 1;
+        if tag & 0x1f == 0x1f {
+            // High tag numbers: base-128 without leading zero digits.
+            if data.get(pos) == Some(&0x80) {
+                out.push(Span::new(offset + start, offset + data.len()));
+                return;
+            }
+            while data.get(pos).is_some_and(|&b| b & 0x80 != 0) {
+                pos += 1;
+            }
+            pos += 1;
+        }
+        let Some(&first) = data.get(pos) else {
+            return;
+        };
+        pos += 1;
+        let (len, minimal) = if first < 0x80 {
+            (first as usize, true)
+        } else if first == 0x80 {
+            // The indefinite form is not allowed in DER, and its end is not searched for.
+            out.push(Span::new(offset + start, offset + data.len()));
+            return;
+        } else {
+            let n = (first & 0x7f) as usize;
+            let Some(bytes) = data.get(pos..pos + n) else {
+                return;
+            };
+            pos += n;
+            let len = bytes.iter().fold(0usize, |acc, &b| {
+                acc.saturating_mul(256).saturating_add(b.into())
+            });
+            (len, bytes[0] != 0 && len >= 0x80)
+        };
+        let Some(content) = data.get(pos..pos.saturating_add(len)) else {
+            return;
+        };
+        let end = pos + len;
+        let span = Span::new(offset + start, offset + end);
+        let minimal = minimal
+            && match tag {
+                // BOOLEAN
+                0x01 => content == [0x00] || content == [0xff],
+                // INTEGER and ENUMERATED
+                0x02 | 0x0a => match content {
+                    [] => false,
+                    [0x00, next, ..] => next & 0x80 != 0,
+                    [0xff, next, ..] => next & 0x80 == 0,
+                    _ => true,
+                },
+                _ => true,
+            };
+        if !minimal {
+            out.push(span);
+        } else if tag & 0x20 != 0 {
+            non_minimal(content, offset + pos, out);
+        }
+        pos = end;
+    }
+}
+
+/// Runs all checks on `cert`, which was parsed from `der`.
+pub fn lint(cert: &Certificate, der: &[u8]) -> Vec<Finding> {
+    let mut linter = Linter {
+        cert,
+        der,
+        findings: vec![],
+    };
+    linter.signature_algorithm();
+    linter.serial_number();
+    linter.version();
+    linter.validity();
+    linter.names();
+    linter.duplicate_extensions();
+    linter.malformed_extensions();
+    linter.ca();
+    linter.key_usage_consistency();
+    linter.key_size();
+    linter.encoding();
+    linter.findings
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn non_minimal_test() {
+        let mut out = vec![];
+        // SEQUENCE { INTEGER 1, BOOLEAN TRUE }
+        non_minimal(
+            &[0x30, 0x06, 0x02, 0x01, 0x01, 0x01, 0x01, 0xff],
+            0,
+            &mut out,
+        );
+        assert_eq!(out, []);
+        // SEQUENCE { INTEGER 1 with a leading zero, BOOLEAN 1 }, in a long-form length
+        non_minimal(
+            &[0x30, 0x81, 0x07, 0x02, 0x02, 0x00, 0x01, 0x01, 0x01, 0x01],
+            10,
+            &mut out,
+        );
+        assert_eq!(out, [Span::new(10, 20)]);
+        out.clear();
+        non_minimal(
+            &[0x30, 0x07, 0x02, 0x02, 0x00, 0x01, 0x01, 0x01, 0x01],
+            10,
+            &mut out,
+        );
+        assert_eq!(out, [Span::new(12, 16), Span::new(16, 19)]);
+    }
+
+    #[test]
+    fn max_tls_validity_days_test() {
+        let at = |year, month, day| time::days_from_civil(year, month, day) * 86400;
+        assert_eq!(max_tls_validity_days(at(2018, 2, 28)), None);
+        assert_eq!(max_tls_validity_days(at(2024, 1, 1)).unwrap().1, 398);
+        assert_eq!(max_tls_validity_days(at(2026, 3, 15) - 1).unwrap().1, 398);
+        assert_eq!(max_tls_validity_days(at(2026, 3, 15)).unwrap().1, 200);
+        assert_eq!(max_tls_validity_days(at(2026, 10, 18)).unwrap().1, 200);
+        assert_eq!(max_tls_validity_days(at(2028, 1, 1)).unwrap().1, 100);
+        assert_eq!(max_tls_validity_days(at(2029, 3, 15)).unwrap().1, 47);
+    }
+}
@@ -1,16 +1,19 @@
-use bpaf::{long, Bpaf, Parser};
-use core::str::FromStr;
-use serde::Serialize;
-use std::fs;
-
+use bpaf::{construct, long, Bpaf, OptionParser, Parser};
+use clavem::document::ParseOptions;
 #[cfg(feature = "der")]
-use clavem::der::privkey::{parse_private_key, PrivateKey};
+use clavem::document::Value;
+use clavem::error::Error;
 #[cfg(feature = "der")]
-use clavem::der::pubkey::{parse_public_key, PublicKey};
+use clavem::lint::Severity;
+use clavem::secret::Policy;
+use clavem::span::Span;
+use clavem::time::Status;
 #[cfg(feature = "der")]
-use clavem::der::{cert, csr, rsa};
-#[cfg(feature = "openssh")]
-use clavem::openssh;
+use clavem::verify::{Key, Outcome};
+use core::str::FromStr;
+use serde::Serialize;
+use std::fs;
+use std::process::ExitCode;
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 enum OutputFormat {
@@ -37,20 +40,176 @@ fn output_format() -> impl Parser<OutputFormat> {
         .fallback(OutputFormat::Json)
 }
 
+/// Policies for displaying secrets that can be selected with `--secrets`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum SecretMode {
+    Redacted,
+    Length,
+    Commitment,
+}
+
+impl FromStr for SecretMode {
+    type Err = &'static str;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "redacted" => Ok(SecretMode::Redacted),
+            "length" => Ok(SecretMode::Length),
+            "commitment" => Ok(SecretMode::Commitment),
+            _ => Err("Invalid secret policy"),
+        }
+    }
+}
+
+fn secret_policy() -> impl Parser<Policy> {
+    let reveal = long("reveal-private")
+        .help("Display private values as is")
+        .req_flag(Policy::Reveal);
+    let mode = long("secrets")
+        .help("How to display private values: redacted, length or commitment")
+        .argument::<SecretMode>("POLICY")
+        .fallback(SecretMode::Redacted);
+    let salt = long("secret-salt")
+        .help("Salt for the commitment policy; use the same salt to compare secrets across files")
+        .argument::<String>("SALT")
+        .optional();
+    let selected = construct!(mode, salt)
+        .guard(
+            |(mode, salt)| (*mode == SecretMode::Commitment) == salt.is_some(),
+            "--secret-salt is required by, and only valid with, --secrets=commitment",
+        )
+        .map(|(mode, salt)| match mode {
+            SecretMode::Redacted => Policy::Redacted,
+            SecretMode::Length => Policy::Length,
+            SecretMode::Commitment => Policy::Commitment {
+                salt: salt.unwrap_or_default().into_bytes(),
+            },
+        });
+    construct!([reveal, selected])
+}
+
+/// Where the passphrase of encrypted private keys is read from. Only encrypted PKCS #8 and PEM
+/// keys are decrypted, which require the `der` feature.
+#[derive(Debug, Clone)]
+enum Passphrase {
+    File(String),
+    Env(String),
+}
+
+impl Passphrase {
+    /// Reads the passphrase. Only the first line of a file is used.
+    fn read(&self) -> std::io::Result<Vec<u8>> {
+        match self {
+            Passphrase::File(filename) => {
+                let data = fs::read(filename)?;
+                let line = data.split(|&b| b == b'\n').next().unwrap_or_default();
+                Ok(line.strip_suffix(b"\r").unwrap_or(line).to_vec())
+            }
+            Passphrase::Env(name) => std::env::var(name).map(String::into_bytes).map_err(|e| {
+                std::io::Error::new(std::io::ErrorKind::InvalidInput, format!("{}: {}", name, e))
+            }),
+        }
+    }
+}
+
+fn passphrase() -> impl Parser<Option<Passphrase>> {
+    let file = long("passphrase-file")
+        .help("Decrypt encrypted private keys with the first line of FILE")
+        .argument::<String>("FILE")
+        .map(Passphrase::File);
+    let env = long("passphrase-env")
+        .help("Decrypt encrypted private keys with the value of the environment variable VAR")
+        .argument::<String>("VAR")
+        .map(Passphrase::Env);
+    construct!([file, env]).optional()
+}
+
+/// A point in time, as a UNIX timestamp.
+#[derive(Debug, Clone, Copy)]
+struct Timestamp(i64);
+
+impl FromStr for Timestamp {
+    type Err = &'static str;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        clavem::time::parse_iso8601(s)
+            .map(Timestamp)
+            .ok_or("Expected YYYY-MM-DD, YYYY-MM-DDTHH:MM:SSZ or @UNIX_TIME")
+    }
+}
+
+/// A duration in seconds.
+#[derive(Debug, Clone, Copy)]
+struct Duration(i64);
+
+impl FromStr for Duration {
+    type Err = &'static str;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        clavem::time::parse_duration(s)
+            .map(Duration)
+            .ok_or("Expected a number followed by s, m, h, d or w")
+    }
+}
+
 #[derive(Debug, Clone, Bpaf)]
-#[bpaf(options)]
+#[cfg_attr(not(feature = "der"), allow(dead_code))]
 pub struct Options {
     #[bpaf(long("display-span"), switch)]
     display_span: bool,
     #[bpaf(external(output_format))]
-    #[allow(dead_code)]
-    output_format: OutputFormat, // TODO: add support for Text
+    output_format: OutputFormat,
     #[bpaf(long, switch)]
     all: bool,
+    /// Display an annotated hex dump of the decoded binary instead
+    #[bpaf(long, switch)]
+    hexdump: bool,
+    #[bpaf(external(secret_policy))]
+    secrets: Policy,
+    #[bpaf(external(passphrase))]
+    passphrase: Option<Passphrase>,
+    /// Check the validity of certificates at TIME instead of now
+    #[bpaf(long, argument("TIME"))]
+    at: Option<Timestamp>,
+    /// Check the validity of certificates and report those expiring within DURATION
+    #[bpaf(long, argument("DURATION"))]
+    expires_within: Option<Duration>,
+    /// Verify the signatures of certificates with the key in FILE, which may be a certificate, a
+    /// certification request or a public key
+    #[bpaf(long, argument("FILE"))]
+    issuer: Option<String>,
+    #[bpaf(positional)]
+    filename: String,
+}
+
+/// Check certificates against RFC 5280 and the CA/Browser Forum Baseline Requirements
+#[cfg(feature = "der")]
+#[derive(Debug, Clone, Bpaf)]
+#[bpaf(command("lint"))]
+pub struct LintOptions {
+    #[bpaf(external(output_format))]
+    output_format: OutputFormat,
     #[bpaf(positional)]
     filename: String,
 }
 
+#[derive(Debug, Clone)]
+enum Command {
+    #[cfg(feature = "der")]
+    Lint(LintOptions),
+    Display(Options),
+}
+
+fn command() -> OptionParser<Command> {
+    let display = options().map(Command::Display);
+    #[cfg(feature = "der")]
+    let display = {
+        let lint = lint_options().map(Command::Lint);
+        construct!([lint, display])
+    };
+    display.to_options()
+}
+
 fn remove_spans(value: &mut serde_json::Value) {
     match value {
         serde_json::Value::Object(map) => {
@@ -68,141 +227,282 @@ fn remove_spans(value: &mut serde_json::Value) {
     }
 }
 
-fn display<T: Serialize>(args: &Options, wrapped: &T) {
-    let mut json_value = serde_json::to_value(wrapped).unwrap();
+/// Displays `wrapped`. `data` is the binary to which the spans in `wrapped` refer.
+fn display<T: Serialize>(args: &Options, data: &[u8], wrapped: &T, status: Option<Status>) {
+    let mut json_value = clavem::secret::to_json(wrapped, &args.secrets).unwrap();
+    if let Some(status) = status {
+        json_value["status"] = serde_json::to_value(status).unwrap();
+    }
+    if args.hexdump {
+        println!("type: {}", json_value["type"].as_str().unwrap_or_default());
+        if let Some(status) = json_value["status"].as_str() {
+            println!("status: {}", status);
+        }
+        println!("{}", clavem::hexdump::render(data, &json_value["value"]));
+        return;
+    }
     if !args.display_span {
         // remove all spans
         remove_spans(&mut json_value);
     }
-    println!("{}", serde_json::to_string_pretty(&json_value).unwrap());
+    match args.output_format {
+        OutputFormat::Text => println!("{}", clavem::text::render(&json_value)),
+        OutputFormat::Json => println!("{}", serde_json::to_string_pretty(&json_value).unwrap()),
+    }
 }
 
-fn parse_as_pem(args: &Options, data: &[u8]) -> pem::Result<()> {
-    let result = pem::parse_many(data)?;
-    if result.is_empty() {
-        return Err(pem::PemError::MissingData);
+/// Classes of failures. Each of them has a distinct exit code.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Failure {
+    /// The input file could not be read.
+    Io,
+    /// The input is not in any of the supported formats.
+    Unsupported,
+    /// The input is malformed.
+    Malformed,
+    /// The input is well-formed but has invalid values.
+    Invalid,
+    /// An encrypted private key could not be decrypted with the passphrase.
+    WrongPassphrase,
+    /// Errors that are not caused by the input.
+    Internal,
+}
+
+impl Failure {
+    fn of(error: &Error) -> Self {
+        match error {
+            Error::UnsupportedFormat
+            | Error::UnsupportedAlgorithm { .. }
+            | Error::UnsupportedCipher(_) => Failure::Unsupported,
+            Error::WrongPassphrase => Failure::WrongPassphrase,
+            Error::InvalidInputError { .. } => Failure::Invalid,
+            Error::JsonSerError(_) => Failure::Internal,
+            Error::PemBlockError { source, .. } => Failure::of(source),
+            _ => Failure::Malformed,
+        }
     }
-    for pem in result {
-        #[cfg(feature = "der")]
-        if pem.tag() == "PUBLIC KEY" {
-            let value = parse_public_key(pem.contents()).unwrap();
-            #[derive(Serialize)]
-            struct Wrapping {
-                #[serde(rename = "type")]
-                ty: &'static str,
-                value: PublicKey,
-            }
-            let wrapped = Wrapping {
-                ty: "PEM public key",
-                value,
-            };
-            display(args, &wrapped);
+
+    fn name(self) -> &'static str {
+        match self {
+            Failure::Io => "io",
+            Failure::Unsupported => "unsupported",
+            Failure::Malformed => "malformed",
+            Failure::Invalid => "invalid",
+            Failure::WrongPassphrase => "wrong-passphrase",
+            Failure::Internal => "internal",
         }
-        #[cfg(feature = "der")]
-        if pem.tag() == "RSA PRIVATE KEY" {
-            let value = rsa::privkey::parse(pem.contents()).unwrap();
-            #[derive(Serialize)]
-            struct Wrapping {
-                #[serde(rename = "type")]
-                ty: &'static str,
-                value: rsa::PrivateKey,
-            }
-            let wrapped = Wrapping {
-                ty: "PEM RSA private key",
-                value,
-            };
-            display(args, &wrapped);
+    }
+
+    // 1 is used by bpaf for invalid arguments, 2 to 5 by failures, 6 to 10 by statuses and
+    // findings, and 11 by a wrong passphrase.
+    fn exit_code(self) -> u8 {
+        match self {
+            Failure::Io => 2,
+            Failure::Unsupported => 3,
+            Failure::Malformed => 4,
+            Failure::Invalid => 5,
+            Failure::WrongPassphrase => 11,
+            Failure::Internal => 70,
         }
-        #[cfg(feature = "der")]
-        if pem.tag() == "PRIVATE KEY" {
-            let value = parse_private_key(pem.contents()).unwrap();
-            #[derive(Serialize)]
-            struct Wrapping {
-                #[serde(rename = "type")]
-                ty: &'static str,
-                value: PrivateKey,
-            }
-            let wrapped = Wrapping {
-                ty: "PEM private key",
-                value,
-            };
-            display(args, &wrapped);
+    }
+}
+
+// Statuses of certificates other than `Valid` have exit codes following those of failures.
+fn status_exit_code(status: Status) -> u8 {
+    match status {
+        Status::Valid => 0,
+        Status::ExpiringSoon => 6,
+        Status::NotYetValid => 7,
+        Status::Expired => 8,
+    }
+}
+
+/// Exit code of certificates whose signature is invalid, which takes precedence over statuses.
+/// Only verifications requested with `--issuer` affect the exit code; self-issued certificates
+/// are verified automatically, but their outcome is only displayed. Certification requests are
+/// always signed by their own key, so an invalid signature on a request also has this code.
+const INVALID_SIGNATURE_EXIT_CODE: u8 = 9;
+
+/// Exit code of `lint` when a certificate has findings of [`Severity::Error`].
+#[cfg(feature = "der")]
+const LINT_ERROR_EXIT_CODE: u8 = 10;
+
+/// Reports a failure, as a JSON object in JSON mode or as a message on stderr otherwise.
+fn report(
+    output_format: OutputFormat,
+    failure: Failure,
+    error: &dyn std::error::Error,
+    location: Option<(&str, Span)>,
+) -> ExitCode {
+    let mut message = error.to_string();
+    let mut source = error.source();
+    while let Some(inner) = source {
+        // Some errors already include the message of their source.
+        let inner_message = inner.to_string();
+        if !message.ends_with(&inner_message) {
+            message += &format!(": {}", inner_message);
         }
-        #[cfg(feature = "der")]
-        if pem.tag() == "CERTIFICATE" {
-            let value = cert::parse(pem.contents()).unwrap();
+        source = inner.source();
+    }
+    match output_format {
+        OutputFormat::Json => {
             #[derive(Serialize)]
-            struct Wrapping {
-                #[serde(rename = "type")]
-                ty: &'static str,
-                value: cert::Certificate,
+            struct Report<'a> {
+                class: &'static str,
+                message: String,
+                #[serde(skip_serializing_if = "Option::is_none")]
+                field: Option<&'a str>,
+                #[serde(skip_serializing_if = "Option::is_none")]
+                span: Option<Span>,
             }
-            let wrapped = Wrapping {
-                ty: "PEM certificate",
-                value,
+            let report = Report {
+                class: failure.name(),
+                message,
+                field: location.map(|(field, _)| field),
+                span: location.map(|(_, span)| span),
             };
-            println!("{}", serde_json::to_string_pretty(&wrapped).unwrap());
+            let value = serde_json::json!({ "error": report });
+            println!("{}", serde_json::to_string_pretty(&value).unwrap());
         }
+        OutputFormat::Text => eprintln!("error: {}", message),
+    }
+    ExitCode::from(failure.exit_code())
+}
+
+fn main() -> ExitCode {
+    match command().run() {
         #[cfg(feature = "der")]
-        if pem.tag() == "CERTIFICATE REQUEST" {
-            let value = csr::parse_csr(pem.contents()).unwrap();
-            #[derive(Serialize)]
-            struct Wrapping {
-                #[serde(rename = "type")]
-                ty: &'static str,
-                value: csr::CertificationRequest,
-            }
-            let wrapped = Wrapping {
-                ty: "PEM certificate request",
-                value,
-            };
-            display(args, &wrapped);
-        }
-        #[cfg(feature = "openssh")]
-        if pem.tag() == "OPENSSH PRIVATE KEY" {
-            let value = openssh::privkey::parse(pem.contents(), 0).unwrap();
-            #[derive(Serialize)]
-            struct Wrapping {
-                #[serde(rename = "type")]
-                ty: &'static str,
-                value: openssh::privkey::PrivateKey,
-            }
-            let wrapped = Wrapping {
-                ty: "OPENSSH private key",
-                value,
-            };
-            display(args, &wrapped);
-        }
+        Command::Lint(args) => lint(&args),
+        Command::Display(args) => display_all(args),
     }
-    Ok(())
 }
 
-fn main() -> Result<(), &'static str> {
-    let mut args: Options = options().run();
+/// Displays the documents in the input file.
+fn display_all(mut args: Options) -> ExitCode {
     let filename = args.filename.clone();
     if args.all {
         args.display_span = true;
     }
-    let data = fs::read(filename).expect("Unable to read file");
-    if let Ok(data) = std::str::from_utf8(&data) {
-        if let Ok(value) = openssh::pubkey::parse(data) {
-            #[derive(Serialize)]
-            struct Wrapping<'a> {
-                #[serde(rename = "type")]
-                ty: &'static str,
-                value: openssh::pubkey::PublicKey<'a>,
+    let passphrase = match args.passphrase.as_ref().map(Passphrase::read).transpose() {
+        Ok(passphrase) => passphrase,
+        Err(e) => return report(args.output_format, Failure::Io, &e, None),
+    };
+    let data = match fs::read(&filename) {
+        Ok(data) => data,
+        Err(e) => return report(args.output_format, Failure::Io, &e, None),
+    };
+    #[cfg_attr(not(feature = "der"), allow(unused_mut))]
+    let mut documents = match clavem::parse_with(&data, &ParseOptions { passphrase }) {
+        Ok(documents) => documents,
+        Err(e) => return report(args.output_format, Failure::of(&e), &e, e.location()),
+    };
+    #[cfg(feature = "der")]
+    if let Some(issuer) = &args.issuer {
+        let key = match load_issuer(&args, issuer) {
+            Ok(key) => key,
+            Err(code) => return code,
+        };
+        for document in &mut documents {
+            if let Value::Certificate(cert) = &mut document.value {
+                cert.verify(&key, issuer);
             }
-            let wrapped = Wrapping {
-                ty: "OPENSSH public key",
-                value,
-            };
-            display(&args, &wrapped);
+        }
+    }
+    // Validity is only checked on request, so that the output does not depend on the current time.
+    let check_at = match (args.at, args.expires_within) {
+        (None, None) => None,
+        (Some(Timestamp(at)), _) => Some(at),
+        (None, Some(_)) => Some(now()),
+    };
+    #[cfg_attr(not(feature = "der"), allow(unused_variables))]
+    let expires_within = args.expires_within.map_or(0, |Duration(d)| d);
+    let mut worst = Status::Valid;
+    #[cfg_attr(not(feature = "der"), allow(unused_mut))]
+    let mut invalid_signature = false;
+    for document in &documents {
+        let status = match (&document.value, check_at) {
+            #[cfg(feature = "der")]
+            (Value::Certificate(cert), Some(at)) => {
+                Some(cert.tbs_certificate.validity.status(at, expires_within))
+            }
+            _ => None,
+        };
+        #[cfg(feature = "der")]
+        if let (Value::Certificate(cert), Some(_)) = (&document.value, &args.issuer) {
+            invalid_signature |=
+                matches!(&cert.verification, Some(v) if v.result == Outcome::Invalid);
+        }
+        #[cfg(feature = "der")]
+        if let Value::CertificationRequest(csr) = &document.value {
+            invalid_signature |= csr.verification.result == Outcome::Invalid;
+        }
+        worst = worst.max(status.unwrap_or(Status::Valid));
+        display(&args, &document.data, document, status);
+    }
+    if invalid_signature {
+        return ExitCode::from(INVALID_SIGNATURE_EXIT_CODE);
+    }
+    ExitCode::from(status_exit_code(worst))
+}
 
-            return Ok(());
+/// Lints the certificates in the input file.
+#[cfg(feature = "der")]
+fn lint(args: &LintOptions) -> ExitCode {
+    let data = match fs::read(&args.filename) {
+        Ok(data) => data,
+        Err(e) => return report(args.output_format, Failure::Io, &e, None),
+    };
+    let documents = match clavem::parse(&data) {
+        Ok(documents) => documents,
+        Err(e) => return report(args.output_format, Failure::of(&e), &e, e.location()),
+    };
+    let mut linted = false;
+    let mut errors = false;
+    for document in &documents {
+        let Value::Certificate(cert) = &document.value else {
+            continue;
+        };
+        let findings = clavem::lint::lint(cert, &document.data);
+        errors |= findings.iter().any(|f| f.severity == Severity::Error);
+        linted = true;
+        let value = serde_json::json!({
+            "type": document.ty,
+            "subject": cert.tbs_certificate.subject.rfc4514,
+            "findings": findings,
+        });
+        match args.output_format {
+            OutputFormat::Text => println!("{}", clavem::text::render(&value)),
+            OutputFormat::Json => println!("{}", serde_json::to_string_pretty(&value).unwrap()),
         }
     }
-    if parse_as_pem(&args, &data).is_ok() {
-        return Ok(());
+    if !linted {
+        let e: Box<dyn std::error::Error> = "no certificates in the input".into();
+        return report(args.output_format, Failure::Unsupported, e.as_ref(), None);
+    }
+    if errors {
+        return ExitCode::from(LINT_ERROR_EXIT_CODE);
     }
-    Err("Unsupported!")
+    ExitCode::SUCCESS
+}
+
+/// Reads the key of the issuer from the first document in `filename` that has one.
+#[cfg(feature = "der")]
+fn load_issuer(args: &Options, filename: &str) -> Result<Key, ExitCode> {
+    let data = fs::read(filename).map_err(|e| report(args.output_format, Failure::Io, &e, None))?;
+    let documents = clavem::parse(&data)
+        .map_err(|e| report(args.output_format, Failure::of(&e), &e, e.location()))?;
+    documents
+        .iter()
+        .find_map(Key::from_document)
+        .ok_or_else(|| {
+            let e: Box<dyn std::error::Error> = "no supported public key in the issuer file".into();
+            report(args.output_format, Failure::Unsupported, e.as_ref(), None)
+        })
+}
+
+fn now() -> i64 {
+    let elapsed = std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .unwrap_or_default();
+    elapsed.as_secs() as i64
 }
@@ -2,6 +2,7 @@ use num_bigint::{BigInt, Sign};
 use serde::Serialize;
 
 use crate::int::DisplayedInt;
+use crate::secret::Secret;
 use crate::span::Span;
 
 use super::error::Result;
@@ -13,7 +14,7 @@ pub struct PrivateKey {
     pub q: DisplayedInt,
     pub g: DisplayedInt,
     pub pub_key: DisplayedInt,
-    pub priv_key: DisplayedInt,
+    pub priv_key: Secret,
 }
 
 // https://github.com/openssh/openssh-portable/blob/V_8_9_P1/sshkey.c#L2438-L2459
@@ -29,19 +30,20 @@ pub mod privkey {
     use super::*;
 
     pub fn parse(content: &[u8], offset: usize) -> Result<(&[u8], Span, PrivateKey)> {
-        let (content, p_span, p) = super::super::parse_bytes(content, offset)?;
-        let (content, q_span, q) = super::super::parse_bytes(content, p_span.end)?;
-        let (content, g_span, g) = super::super::parse_bytes(content, q_span.end)?;
-        let (content, pub_key_span, pub_key) = super::super::parse_bytes(content, g_span.end)?;
+        let (content, p_span, p) = super::super::parse_bytes(content, offset, "p")?;
+        let (content, q_span, q) = super::super::parse_bytes(content, p_span.end, "q")?;
+        let (content, g_span, g) = super::super::parse_bytes(content, q_span.end, "g")?;
+        let (content, pub_key_span, pub_key) =
+            super::super::parse_bytes(content, g_span.end, "pub_key")?;
         let (content, priv_key_span, priv_key) =
-            super::super::parse_bytes(content, pub_key_span.end)?;
+            super::super::parse_bytes(content, pub_key_span.end, "priv_key")?;
 
         let wrapped = PrivateKey {
             p: DisplayedInt::new(BigInt::from_bytes_be(Sign::Plus, p), p_span),
             q: DisplayedInt::new(BigInt::from_bytes_be(Sign::Plus, q), q_span),
             g: DisplayedInt::new(BigInt::from_bytes_be(Sign::Plus, g), g_span),
             pub_key: DisplayedInt::new(BigInt::from_bytes_be(Sign::Plus, pub_key), pub_key_span),
-            priv_key: DisplayedInt::new(BigInt::from_bytes_be(Sign::Plus, priv_key), priv_key_span),
+            priv_key: Secret::integer(&BigInt::from_bytes_be(Sign::Plus, priv_key), priv_key_span),
         };
         Ok((content, Span::new(offset, priv_key_span.end), wrapped))
     }
@@ -51,10 +53,11 @@ pub mod pubkey {
     use super::*;
 
     pub fn parse(content: &[u8], offset: usize) -> Result<(&[u8], Span, PublicKey)> {
-        let (content, p_span, p) = super::super::parse_bytes(content, offset)?;
-        let (content, q_span, q) = super::super::parse_bytes(content, p_span.end)?;
-        let (content, g_span, g) = super::super::parse_bytes(content, q_span.end)?;
-        let (content, pub_key_span, pub_key) = super::super::parse_bytes(content, g_span.end)?;
+        let (content, p_span, p) = super::super::parse_bytes(content, offset, "p")?;
+        let (content, q_span, q) = super::super::parse_bytes(content, p_span.end, "q")?;
+        let (content, g_span, g) = super::super::parse_bytes(content, q_span.end, "g")?;
+        let (content, pub_key_span, pub_key) =
+            super::super::parse_bytes(content, g_span.end, "pub_key")?;
 
         let wrapped = PublicKey {
             p: DisplayedInt::new(BigInt::from_bytes_be(Sign::Plus, p), p_span),
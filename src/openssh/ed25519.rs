@@ -2,13 +2,14 @@ use num_bigint::{BigInt, Sign};
 use serde::Serialize;
 
 use crate::int::DisplayedInt;
+use crate::secret::Secret;
 use crate::span::Span;
 
-use super::error::Result;
+use super::error::{Error, Result};
 
 #[derive(Serialize)]
 pub struct Sk {
-    pub priv_part: DisplayedInt,
+    pub priv_part: Secret,
     pub pub_part: DisplayedInt,
 }
 
@@ -23,11 +24,17 @@ pub mod privkey {
     use super::*;
 
     pub fn parse(content: &[u8], offset: usize) -> Result<(&[u8], Span, PrivateKey)> {
-        let (content, pk_span, pk) = super::super::parse_bytes(content, offset)?;
-        let (content, sk_span, sk) = super::super::parse_bytes(content, pk_span.end)?;
+        let (content, pk_span, pk) = super::super::parse_bytes(content, offset, "pk")?;
+        let (content, sk_span, sk) = super::super::parse_bytes(content, pk_span.end, "sk")?;
+        if sk.len() != 64 {
+            return Err(Error::ParseError {
+                field: "sk",
+                span: sk_span,
+            });
+        }
         let sk = Sk {
-            priv_part: DisplayedInt::new(
-                BigInt::from_bytes_be(Sign::Plus, &sk[..32]),
+            priv_part: Secret::bytes(
+                &sk[..32],
                 Span {
                     start: sk_span.start + 4,
                     end: sk_span.start + 36,
@@ -55,7 +62,7 @@ pub mod pubkey {
     use super::*;
 
     pub fn parse(content: &[u8], offset: usize) -> Result<(&[u8], Span, BitStr)> {
-        let (content, pk_span, pk) = super::super::parse_bytes(content, offset)?;
+        let (content, pk_span, pk) = super::super::parse_bytes(content, offset, "pk")?;
         Ok((content, pk_span, BitStr::from(pk)))
     }
 }
@@ -1,3 +1,5 @@
+use crate::span::Span;
+
 #[derive(Debug, thiserror::Error)]
 #[non_exhaustive]
 pub enum Error {
@@ -7,20 +9,34 @@ pub enum Error {
         #[source]
         serde_json::Error,
     ),
-    #[error("Parsing failed")]
-    ParseError,
-    #[error("UTF-8 conversion failed")]
-    UTF8ConversionError(
-        #[from]
+    #[error("Parsing `{field}` at bytes {span} failed")]
+    ParseError { field: &'static str, span: Span },
+    #[error("`{field}` at bytes {span} is not valid UTF-8")]
+    UTF8ConversionError {
+        field: &'static str,
+        span: Span,
         #[source]
-        std::string::FromUtf8Error,
-    ),
-    #[error("base64 decoding failed")]
-    Base64DecodeError(
-        #[from]
+        source: std::string::FromUtf8Error,
+    },
+    #[error("base64 decoding of `{field}` at bytes {span} failed")]
+    Base64DecodeError {
+        field: &'static str,
+        span: Span,
         #[source]
-        base64::DecodeError,
-    ),
+        source: base64::DecodeError,
+    },
+}
+
+impl Error {
+    /// Returns the name and the location of the field that could not be parsed, if known.
+    pub fn location(&self) -> Option<(&'static str, Span)> {
+        match *self {
+            Error::ParseError { field, span }
+            | Error::UTF8ConversionError { field, span, .. }
+            | Error::Base64DecodeError { field, span, .. } => Some((field, span)),
+            _ => None,
+        }
+    }
 }
 
 pub type Result<T> = std::result::Result<T, Error>;
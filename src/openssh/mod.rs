@@ -12,38 +12,81 @@ pub mod pubkey;
 pub mod rsa;
 
 // nom-like parsing functions
+// `field` is the name of the field being parsed, which is reported on failure.
 
-fn parse_tag<'a>(content: &'a [u8], offset: usize, tag: &[u8]) -> Result<(&'a [u8], Span)> {
+fn parse_tag<'a>(
+    content: &'a [u8],
+    offset: usize,
+    tag: &[u8],
+    field: &'static str,
+) -> Result<(&'a [u8], Span)> {
+    let span = Span::new(offset, offset + tag.len());
     if content.len() < tag.len() || &content[..tag.len()] != tag {
-        return Err(Error::ParseError);
+        return Err(Error::ParseError { field, span });
     }
-    Ok((&content[tag.len()..], Span::new(offset, offset + tag.len())))
+    Ok((&content[tag.len()..], span))
 }
 
-fn parse_u32(content: &[u8], offset: usize) -> Result<(&[u8], Span, u32)> {
+fn parse_u32<'a>(
+    content: &'a [u8],
+    offset: usize,
+    field: &'static str,
+) -> Result<(&'a [u8], Span, u32)> {
+    let span = Span::new(offset, offset + 4);
     if content.len() < 4 {
-        return Err(Error::ParseError);
+        return Err(Error::ParseError { field, span });
     }
     let value = u32::from_be_bytes(<[u8; 4]>::try_from(&content[..4]).unwrap());
-    Ok((&content[4..], Span::new(offset, offset + 4), value))
+    Ok((&content[4..], span, value))
 }
 
-fn parse_u64(content: &[u8], offset: usize) -> Result<(&[u8], Span, u64)> {
+fn parse_u64<'a>(
+    content: &'a [u8],
+    offset: usize,
+    field: &'static str,
+) -> Result<(&'a [u8], Span, u64)> {
+    let span = Span::new(offset, offset + 8);
     if content.len() < 8 {
-        return Err(Error::ParseError);
+        return Err(Error::ParseError { field, span });
     }
     let value = u64::from_be_bytes(<[u8; 8]>::try_from(&content[..8]).unwrap());
-    Ok((&content[8..], Span::new(offset, offset + 8), value))
+    Ok((&content[8..], span, value))
 }
 
-fn parse_bytes(content: &[u8], offset: usize) -> Result<(&[u8], Span, &[u8])> {
-    let (content, _, len) = parse_u32(content, offset)?;
+fn parse_bytes<'a>(
+    content: &'a [u8],
+    offset: usize,
+    field: &'static str,
+) -> Result<(&'a [u8], Span, &'a [u8])> {
+    let (content, _, len) = parse_u32(content, offset, field)?;
+    let span = Span::new(offset, offset + 4 + len as usize);
     if content.len() < len as usize {
-        return Err(Error::ParseError);
+        return Err(Error::ParseError { field, span });
     }
-    Ok((
-        &content[len as usize..],
-        Span::new(offset, offset + 4 + len as usize),
-        &content[..len as usize],
-    ))
+    Ok((&content[len as usize..], span, &content[..len as usize]))
+}
+
+fn parse_string<'a>(
+    content: &'a [u8],
+    offset: usize,
+    field: &'static str,
+) -> Result<(&'a [u8], Span, String)> {
+    let (content, span, value) = parse_bytes(content, offset, field)?;
+    let value = String::from_utf8(value.to_vec()).map_err(|source| Error::UTF8ConversionError {
+        field,
+        span,
+        source,
+    })?;
+    Ok((content, span, value))
+}
+
+/// Fails if `content`, which starts at `offset`, is not empty.
+fn expect_end(content: &[u8], offset: usize, field: &'static str) -> Result<()> {
+    if !content.is_empty() {
+        return Err(Error::ParseError {
+            field,
+            span: Span::new(offset, offset + content.len()),
+        });
+    }
+    Ok(())
 }
@@ -6,11 +6,24 @@ use super::error::{Error, Result};
 
 const HEADER: &[u8] = b"openssh-key-v1\0";
 
+/// The decoded private key, which holds secrets.
+#[derive(Serialize)]
+#[serde(untagged)]
+pub enum PrivKey {
+    Ecdsa(super::ecdsa::PrivateKey),
+    Dsa(super::dsa::PrivateKey),
+    Ed25519(super::ed25519::PrivateKey),
+    Rsa(super::rsa::PrivateKey),
+    /// The algorithm is not supported.
+    Unknown(&'static str),
+}
+
 #[derive(Serialize)]
 pub struct PrivPart {
     pub checksum: DisplayedInt,
     pub algo: String,
-    pub content: serde_json::Value,
+    /// `None` if the key is encrypted.
+    pub content: Option<PrivKey>,
     pub comment: String,
     pub span: Span,
 }
@@ -24,104 +37,117 @@ pub struct PrivateKey {
     pub priv_part: PrivPart,
 }
 
-pub fn parse_priv_part(content: &[u8], offset: usize) -> Result<PrivPart> {
-    let (content, rand0_span, rand0) = super::parse_u32(content, offset)?;
-    let (content, rand1_span, rand1) = super::parse_u32(content, rand0_span.end)?;
+/// `pub_part` is the public key blob of the file, with which RSA private keys are checked.
+pub fn parse_priv_part(content: &[u8], offset: usize, pub_part: &[u8]) -> Result<PrivPart> {
+    let (content, rand0_span, rand0) = super::parse_u32(content, offset, "checksum")?;
+    let (content, rand1_span, rand1) = super::parse_u32(content, rand0_span.end, "checksum")?;
     if rand0 != rand1 {
-        return Err(Error::ParseError);
+        return Err(Error::ParseError {
+            field: "checksum",
+            span: Span::new(rand0_span.start, rand1_span.end),
+        });
     }
-    let (content, algo_span, algo) = super::parse_bytes(content, rand1_span.end)?;
-    let algo = String::from_utf8(algo.to_vec())?;
+    let (content, algo_span, algo) = super::parse_string(content, rand1_span.end, "algo")?;
     let mut wrapped = PrivPart {
         checksum: DisplayedInt::new(rand0.into(), Span::new(rand0_span.start, rand1_span.end)),
         algo: algo.clone(),
-        content: serde_json::Value::String("unknown algorithm".to_string()),
+        content: Some(PrivKey::Unknown("unknown algorithm")),
         comment: "".to_string(),
         span: Span::new(offset, content.len() + offset),
     };
     if algo == "ecdsa-sha2-nistp256" {
         let (content, priv_key_span, priv_key) =
             super::ecdsa::privkey::parse(content, algo_span.end)?;
-        let (content, _comment_span, comment) = super::parse_bytes(content, priv_key_span.end)?;
-        if content.len() >= 8 {
-            return Err(Error::ParseError);
-        }
-        wrapped.content = serde_json::to_value(priv_key)?;
-        wrapped.comment = String::from_utf8(comment.to_vec())?;
+        let (content, comment_span, comment) =
+            super::parse_string(content, priv_key_span.end, "comment")?;
+        check_padding(content, comment_span.end)?;
+        wrapped.content = Some(PrivKey::Ecdsa(priv_key));
+        wrapped.comment = comment;
         wrapped.span = priv_key_span;
     }
     if algo == "ssh-dss" {
         let (content, priv_key_span, priv_key) =
             super::dsa::privkey::parse(content, algo_span.end)?;
-        let (content, _comment_span, comment) = super::parse_bytes(content, priv_key_span.end)?;
-        if content.len() >= 8 {
-            return Err(Error::ParseError);
-        }
-        wrapped.content = serde_json::to_value(priv_key)?;
-        wrapped.comment = String::from_utf8(comment.to_vec())?;
+        let (content, comment_span, comment) =
+            super::parse_string(content, priv_key_span.end, "comment")?;
+        check_padding(content, comment_span.end)?;
+        wrapped.content = Some(PrivKey::Dsa(priv_key));
+        wrapped.comment = comment;
         wrapped.span = priv_key_span;
     }
     if algo == "ssh-ed25519" {
         let (content, priv_key_span, priv_key) =
             super::ed25519::privkey::parse(content, algo_span.end)?;
-        let (content, _comment_span, comment) = super::parse_bytes(content, priv_key_span.end)?;
-        if content.len() >= 8 {
-            return Err(Error::ParseError);
-        }
-        wrapped.content = serde_json::to_value(priv_key)?;
-        wrapped.comment = String::from_utf8(comment.to_vec())?;
+        let (content, comment_span, comment) =
+            super::parse_string(content, priv_key_span.end, "comment")?;
+        check_padding(content, comment_span.end)?;
+        wrapped.content = Some(PrivKey::Ed25519(priv_key));
+        wrapped.comment = comment;
         wrapped.span = priv_key_span;
     }
     if algo == "ssh-rsa" {
         let (content, priv_key_span, priv_key) =
-            super::rsa::privkey::parse(content, algo_span.end)?;
-        let (content, _comment_span, comment) = super::parse_bytes(content, priv_key_span.end)?;
-        if content.len() >= 8 {
-            return Err(Error::ParseError);
-        }
-        wrapped.content = serde_json::to_value(priv_key)?;
-        wrapped.comment = String::from_utf8(comment.to_vec())?;
+            super::rsa::privkey::parse(content, algo_span.end, pub_part)?;
+        let (content, comment_span, comment) =
+            super::parse_string(content, priv_key_span.end, "comment")?;
+        check_padding(content, comment_span.end)?;
+        wrapped.content = Some(PrivKey::Rsa(priv_key));
+        wrapped.comment = comment;
         wrapped.span = priv_key_span;
     }
     Ok(wrapped)
 }
 
+/// Padding is shorter than the cipher block size (8 bytes for "none").
+fn check_padding(content: &[u8], offset: usize) -> Result<()> {
+    if content.len() >= 8 {
+        return Err(Error::ParseError {
+            field: "padding",
+            span: Span::new(offset, offset + content.len()),
+        });
+    }
+    Ok(())
+}
+
 // Reference: https://coolaj86.com/articles/the-openssh-private-key-format/
 pub fn parse(content: &[u8], offset: usize) -> Result<PrivateKey> {
-    let (content, tag_span) = super::parse_tag(content, offset, HEADER)?;
-    let (content, ciphername_span, ciphername) = super::parse_bytes(content, tag_span.end)?;
-    let (content, kdfname_span, kdfname) = super::parse_bytes(content, ciphername_span.end)?;
-    let (content, kdf_span, kdf) = super::parse_bytes(content, kdfname_span.end)?;
-    let (content, numkeys_span, numkeys) = super::parse_u32(content, kdf_span.end)?;
+    let (content, tag_span) = super::parse_tag(content, offset, HEADER, "magic")?;
+    let (content, ciphername_span, ciphername) =
+        super::parse_string(content, tag_span.end, "ciphername")?;
+    let (content, kdfname_span, kdfname) =
+        super::parse_string(content, ciphername_span.end, "kdfname")?;
+    let (content, kdf_span, kdf) = super::parse_bytes(content, kdfname_span.end, "kdf")?;
+    let (content, numkeys_span, numkeys) = super::parse_u32(content, kdf_span.end, "numkeys")?;
     if numkeys != 1 {
-        return Err(Error::ParseError);
+        return Err(Error::ParseError {
+            field: "numkeys",
+            span: numkeys_span,
+        });
     }
-    let (content, pub_part_span, pub_part) = super::parse_bytes(content, numkeys_span.end)?;
-    let (content, priv_part_span, priv_part) = super::parse_bytes(content, pub_part_span.end)?;
-    if !content.is_empty() {
-        return Err(Error::ParseError);
-    }
-    let (remaining, _pub_part_inner_span, pub_part_inner) =
+    let (content, pub_part_span, pub_part) =
+        super::parse_bytes(content, numkeys_span.end, "pub_part")?;
+    let (content, priv_part_span, priv_part) =
+        super::parse_bytes(content, pub_part_span.end, "priv_part")?;
+    super::expect_end(content, priv_part_span.end, "trailing data")?;
+    let (remaining, pub_part_inner_span, pub_part_inner) =
         super::pubkey::parse_data(pub_part, pub_part_span.start + 4)?;
-    if !remaining.is_empty() {
-        return Err(Error::ParseError);
-    }
+    super::expect_end(remaining, pub_part_inner_span.end, "pub_part")?;
 
     let mut wrapped = PrivateKey {
-        ciphername: String::from_utf8(ciphername.to_vec())?,
-        kdfname: String::from_utf8(kdfname.to_vec())?,
+        ciphername,
+        kdfname,
         kdf: kdf.into(),
         pub_part: pub_part_inner,
         priv_part: PrivPart {
             checksum: DisplayedInt::new(0.into(), priv_part_span),
             algo: "unknown".to_owned(),
-            content: serde_json::Value::Null,
+            content: None,
             comment: "encrypted key".to_owned(),
             span: priv_part_span,
         },
     };
     if wrapped.ciphername == "none" {
-        let priv_part = parse_priv_part(priv_part, priv_part_span.start + 4)?;
+        let priv_part = parse_priv_part(priv_part, priv_part_span.start + 4, pub_part)?;
         wrapped.priv_part = priv_part;
     }
     Ok(wrapped)
@@ -1,7 +1,7 @@
 use base64::Engine;
 use serde::Serialize;
 
-use crate::{span::Span, string::BitStr};
+use crate::{fingerprint::OpensshFingerprint, span::Span, string::BitStr};
 
 use super::{
     cert::PublicKeyCertificate,
@@ -11,21 +11,29 @@ use super::{
 #[derive(Serialize)]
 pub struct PubPart {
     pub algo: String,
+    /// Set once the whole key has been parsed. Certificates only have the fingerprint of their
+    /// inner key.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub fingerprint: Option<OpensshFingerprint>,
     pub content: serde_json::Value,
     pub span: Span,
 }
 
 #[derive(Serialize)]
-pub struct PublicKey<'a> {
+pub struct PublicKey {
     pub data: PubPart,
-    pub comment: Option<&'a str>,
+    pub comment: Option<String>,
+    /// The base64-decoded key, to which the spans in `data` refer.
+    #[serde(skip)]
+    pub blob: Vec<u8>,
 }
 
 pub fn parse_data(content: &[u8], offset: usize) -> Result<(&[u8], Span, PubPart)> {
-    let (mut content, algo_span, algo) = super::parse_bytes(content, offset)?;
-    let algo = String::from_utf8(algo.to_vec())?;
+    let data = content;
+    let (mut content, algo_span, algo) = super::parse_string(content, offset, "algo")?;
     let mut wrapped = PubPart {
         algo: algo.clone(),
+        fingerprint: None,
         content: serde_json::Value::Null,
         span: Span::new(offset, content.len() + offset),
     };
@@ -65,9 +73,11 @@ pub fn parse_data(content: &[u8], offset: usize) -> Result<(&[u8], Span, PubPart
         // Certificates
         // Format: https://github.com/openssh/openssh-portable/blob/V_9_1_P1/PROTOCOL.certkeys
         parsed = true;
-        let (mut remaining, nonce_span, nonce) = super::parse_bytes(content, algo_span.end)?;
+        let (mut remaining, nonce_span, nonce) =
+            super::parse_bytes(content, algo_span.end, "nonce")?;
         let mut wrapped2 = PubPart {
             algo: algo.clone(),
+            fingerprint: None,
             content: serde_json::Value::Null,
             span: Span::new(offset, content.len() + offset),
         };
@@ -106,28 +116,38 @@ pub fn parse_data(content: &[u8], offset: usize) -> Result<(&[u8], Span, PubPart
             key_span_end2 = pub_key_span.end;
         }
         if !parsed {
-            return Err(Error::ParseError);
+            return Err(Error::ParseError {
+                field: "algo",
+                span: algo_span,
+            });
         }
         wrapped2.span = Span::new(nonce_span.end, key_span_end2);
-        let (remaining, serial_span, serial) = super::parse_u64(remaining, key_span_end2)?;
-        let (remaining, type_span, type_) = super::parse_u32(remaining, serial_span.end)?;
-        let (remaining, key_id_span, key_id) = super::parse_bytes(remaining, type_span.end)?;
+        // The inner key is fingerprinted as the plain key it certifies.
+        let mut inner_blob = (algo_inner.len() as u32).to_be_bytes().to_vec();
+        inner_blob.extend_from_slice(algo_inner.as_bytes());
+        inner_blob.extend_from_slice(&data[nonce_span.end - offset..key_span_end2 - offset]);
+        wrapped2.fingerprint = Some(OpensshFingerprint::new(&inner_blob));
+        let (remaining, serial_span, serial) =
+            super::parse_u64(remaining, key_span_end2, "serial")?;
+        let (remaining, type_span, type_) = super::parse_u32(remaining, serial_span.end, "type")?;
+        let (remaining, key_id_span, key_id) =
+            super::parse_bytes(remaining, type_span.end, "key_id")?;
         let (remaining, valid_principals_span, valid_principals) =
-            super::parse_bytes(remaining, key_id_span.end)?;
+            super::parse_bytes(remaining, key_id_span.end, "valid_principals")?;
         let (remaining, valid_after_span, valid_after) =
-            super::parse_u64(remaining, valid_principals_span.end)?;
+            super::parse_u64(remaining, valid_principals_span.end, "valid_after")?;
         let (remaining, valid_before_span, valid_before) =
-            super::parse_u64(remaining, valid_after_span.end)?;
+            super::parse_u64(remaining, valid_after_span.end, "valid_before")?;
         let (remaining, critical_options_span, critical_options) =
-            super::parse_bytes(remaining, valid_before_span.end)?;
+            super::parse_bytes(remaining, valid_before_span.end, "critical_options")?;
         let (remaining, extensions_span, extensions) =
-            super::parse_bytes(remaining, critical_options_span.end)?;
+            super::parse_bytes(remaining, critical_options_span.end, "extensions")?;
         let (remaining, reserved_span, reserved) =
-            super::parse_bytes(remaining, extensions_span.end)?;
+            super::parse_bytes(remaining, extensions_span.end, "reserved")?;
         let (remaining, signature_key_span, signature_key) =
-            super::parse_bytes(remaining, reserved_span.end)?;
+            super::parse_bytes(remaining, reserved_span.end, "signature_key")?;
         let (remaining, signature_span, signature) =
-            super::parse_bytes(remaining, signature_key_span.end)?;
+            super::parse_bytes(remaining, signature_key_span.end, "signature")?;
         let pubkey_certificate = PublicKeyCertificate {
             nonce: BitStr::from(nonce),
             inner: serde_json::to_value(&wrapped2)?,
@@ -149,15 +169,24 @@ pub fn parse_data(content: &[u8], offset: usize) -> Result<(&[u8], Span, PubPart
         key_span_end = signature_span.end;
     }
     if !parsed {
-        return Err(Error::ParseError);
+        return Err(Error::ParseError {
+            field: "algo",
+            span: algo_span,
+        });
     }
     wrapped.span = Span::new(offset, key_span_end);
+    // Certificates are not fingerprinted as a whole, since their nonce and signature are random.
+    if !algo.ends_with("-cert-v01@openssh.com") {
+        wrapped.fingerprint = Some(OpensshFingerprint::new(&data[..key_span_end - offset]));
+    }
     Ok((content, wrapped.span, wrapped))
 }
 
 /// Parse a string in AUTHORIZED_KEYS FILE FORMAT as in [sshd's manual](https://man.openbsd.org/OpenBSD-7.0/sshd#AUTHORIZED_KEYS_FILE_FORMAT).
 ///
 /// `key` must be a single line: it must contain a newline at the end, and must not contain any other newlines.
+///
+/// Errors in the line itself are reported with spans in `key`, and errors in the key data with spans in the decoded data.
 pub fn parse(key: &str) -> Result<PublicKey> {
     // TODO: support options
 
@@ -168,21 +197,42 @@ pub fn parse(key: &str) -> Result<PublicKey> {
     } else if let Some(s) = key.strip_suffix('\r') {
         s
     } else {
-        return Err(Error::ParseError);
+        return Err(Error::ParseError {
+            field: "newline",
+            span: Span::new(key.len(), key.len()),
+        });
     };
     let s: Vec<_> = stripped.split(' ').collect();
     if s.len() != 2 && s.len() != 3 {
-        return Err(Error::ParseError);
+        return Err(Error::ParseError {
+            field: "line",
+            span: Span::new(0, stripped.len()),
+        });
     }
     let algo = s[0];
     let data = s[1];
-    let comment = s.get(2).copied();
-    let data = base64::prelude::BASE64_STANDARD.decode(data)?;
-    let (remaining, _span, data) = parse_data(&data, 0)?;
-    if !remaining.is_empty() || data.algo != algo {
-        return Err(Error::ParseError);
+    let comment = s.get(2).map(|comment| comment.to_string());
+    let data_span = Span::new(algo.len() + 1, algo.len() + 1 + data.len());
+    let blob = base64::prelude::BASE64_STANDARD
+        .decode(data)
+        .map_err(|source| Error::Base64DecodeError {
+            field: "data",
+            span: data_span,
+            source,
+        })?;
+    let (remaining, span, data) = parse_data(&blob, 0)?;
+    super::expect_end(remaining, span.end, "data")?;
+    if data.algo != algo {
+        return Err(Error::ParseError {
+            field: "algo",
+            span: Span::new(0, algo.len()),
+        });
     }
-    Ok(PublicKey { data, comment })
+    Ok(PublicKey {
+        data,
+        comment,
+        blob,
+    })
 }
 
 #[cfg(test)]
@@ -197,7 +247,7 @@ mod tests {
             assert!(result.is_ok());
             let result = result.unwrap();
             assert_eq!(result.data.algo, "ssh-ed25519");
-            assert_eq!(result.comment, Some("test"));
+            assert_eq!(result.comment.as_deref(), Some("test"));
         }
     }
 
@@ -209,7 +259,7 @@ mod tests {
             let key =
             "ssh-rsa AAAAC3NzaC1lZDI1NTE5AAAAIF/cpeiuO8aMA4abrDs87slcIRMK/AhG3WNVKg7X48Aj test\n";
             let result = parse(key);
-            assert!(matches!(result, Err(Error::ParseError)));
+            assert!(matches!(result, Err(Error::ParseError { .. })));
         }
     }
 
@@ -224,7 +274,7 @@ mod tests {
             assert!(result.is_ok());
             let result = result.unwrap();
             assert_eq!(result.data.algo, "ssh-ed25519");
-            assert_eq!(result.comment, None);
+            assert_eq!(result.comment.as_deref(), None);
         }
 
         #[test]
@@ -233,14 +283,14 @@ mod tests {
             let key =
             "ssh-ed25519 AAAAC3NzaC1lZDI1NTE5AAAAIF/cpeiuO8aMA4abrDs87slcIRMK/AhG3WNVKg7X48Aj test";
             let result = parse(key);
-            assert!(matches!(result, Err(Error::ParseError)));
+            assert!(matches!(result, Err(Error::ParseError { .. })));
         }
         #[test]
         fn parse_test_negative_1() {
             // Algorithm missing
             let key = "AAAAC3NzaC1lZDI1NTE5AAAAIF/cpeiuO8aMA4abrDs87slcIRMK/AhG3WNVKg7X48Aj test";
             let result = parse(key);
-            assert!(matches!(result, Err(Error::ParseError)));
+            assert!(matches!(result, Err(Error::ParseError { .. })));
         }
     }
 }
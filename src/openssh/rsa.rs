@@ -1,7 +1,9 @@
-use num_bigint::{BigInt, Sign};
+use num_bigint::{BigInt, BigUint, Sign};
 use serde::Serialize;
 
+use crate::consistency::{Check, RsaComponents};
 use crate::int::DisplayedInt;
+use crate::secret::Secret;
 use crate::span::Span;
 
 use super::error::Result;
@@ -11,10 +13,12 @@ use super::error::Result;
 pub struct PrivateKey {
     pub n: DisplayedInt,
     pub e: DisplayedInt,
-    pub d: DisplayedInt,
-    pub iqmp: DisplayedInt,
-    pub p: DisplayedInt,
-    pub q: DisplayedInt,
+    pub d: Secret,
+    pub iqmp: Secret,
+    pub p: Secret,
+    pub q: Secret,
+    /// Consistency checks of the components and of the public key of the file.
+    pub checks: Vec<Check>,
 }
 
 // https://github.com/openssh/openssh-portable/blob/V_8_9_P1/sshkey.c#L2410-L2423
@@ -27,20 +31,48 @@ pub struct PublicKey {
 pub mod privkey {
     use super::*;
 
-    pub fn parse(content: &[u8], offset: usize) -> Result<(&[u8], Span, PrivateKey)> {
-        let (content, n_span, n) = super::super::parse_bytes(content, offset)?;
-        let (content, e_span, e) = super::super::parse_bytes(content, n_span.end)?;
-        let (content, d_span, d) = super::super::parse_bytes(content, e_span.end)?;
-        let (content, iqmp_span, iqmp) = super::super::parse_bytes(content, d_span.end)?;
-        let (content, p_span, p) = super::super::parse_bytes(content, iqmp_span.end)?;
-        let (content, q_span, q) = super::super::parse_bytes(content, p_span.end)?;
+    /// Returns e and n of the public key blob `public`.
+    fn public_components(public: &[u8]) -> Option<(BigUint, BigUint)> {
+        let (public, _, _) = super::super::parse_string(public, 0, "algo").ok()?;
+        let (public, _, e) = super::super::parse_bytes(public, 0, "e").ok()?;
+        let (_, _, n) = super::super::parse_bytes(public, 0, "n").ok()?;
+        Some((BigUint::from_bytes_be(e), BigUint::from_bytes_be(n)))
+    }
+
+    /// `public` is the public key blob of the file, which must agree with the private key.
+    pub fn parse<'a>(
+        content: &'a [u8],
+        offset: usize,
+        public: &[u8],
+    ) -> Result<(&'a [u8], Span, PrivateKey)> {
+        let (content, n_span, n) = super::super::parse_bytes(content, offset, "n")?;
+        let (content, e_span, e) = super::super::parse_bytes(content, n_span.end, "e")?;
+        let (content, d_span, d) = super::super::parse_bytes(content, e_span.end, "d")?;
+        let (content, iqmp_span, iqmp) = super::super::parse_bytes(content, d_span.end, "iqmp")?;
+        let (content, p_span, p) = super::super::parse_bytes(content, iqmp_span.end, "p")?;
+        let (content, q_span, q) = super::super::parse_bytes(content, p_span.end, "q")?;
+        // OpenSSH does not store the CRT exponents.
+        let components = RsaComponents {
+            n: BigUint::from_bytes_be(n),
+            e: BigUint::from_bytes_be(e),
+            d: BigUint::from_bytes_be(d),
+            primes: vec![BigUint::from_bytes_be(p), BigUint::from_bytes_be(q)],
+            exponents: Vec::new(),
+            coefficients: vec![BigUint::from_bytes_be(iqmp)],
+        };
+        let mut checks = components.check();
+        checks.push(Check::new(
+            "public-key",
+            public_components(public) == Some((components.e, components.n)),
+        ));
         let wrapped = PrivateKey {
             n: DisplayedInt::new(BigInt::from_bytes_be(Sign::Plus, n), n_span),
             e: DisplayedInt::new(BigInt::from_bytes_be(Sign::Plus, e), e_span),
-            d: DisplayedInt::new(BigInt::from_bytes_be(Sign::Plus, d), d_span),
-            iqmp: DisplayedInt::new(BigInt::from_bytes_be(Sign::Plus, iqmp), iqmp_span),
-            p: DisplayedInt::new(BigInt::from_bytes_be(Sign::Plus, p), p_span),
-            q: DisplayedInt::new(BigInt::from_bytes_be(Sign::Plus, q), q_span),
+            d: Secret::integer(&BigInt::from_bytes_be(Sign::Plus, d), d_span),
+            iqmp: Secret::integer(&BigInt::from_bytes_be(Sign::Plus, iqmp), iqmp_span),
+            p: Secret::integer(&BigInt::from_bytes_be(Sign::Plus, p), p_span),
+            q: Secret::integer(&BigInt::from_bytes_be(Sign::Plus, q), q_span),
+            checks,
         };
         Ok((content, Span::new(offset, q_span.end), wrapped))
     }
@@ -50,8 +82,8 @@ pub mod pubkey {
     use super::*;
 
     pub fn parse(content: &[u8], offset: usize) -> Result<(&[u8], Span, PublicKey)> {
-        let (content, e_span, e) = super::super::parse_bytes(content, offset)?;
-        let (content, n_span, n) = super::super::parse_bytes(content, e_span.end)?;
+        let (content, e_span, e) = super::super::parse_bytes(content, offset, "e")?;
+        let (content, n_span, n) = super::super::parse_bytes(content, e_span.end, "n")?;
         let wrapped = PublicKey {
             e: DisplayedInt::new(BigInt::from_bytes_be(Sign::Plus, e), e_span),
             n: DisplayedInt::new(BigInt::from_bytes_be(Sign::Plus, n), n_span),
@@ -0,0 +1,193 @@
+//! Secret values, such as private exponents and private scalars.
+//!
+//! Parsed documents hold secrets as they are, and a [`Policy`] decides how they are displayed
+//! when the documents are serialized with [`to_json`]. Secrets serialized otherwise are
+//! displayed with [`Policy::Redacted`].
+use std::cell::RefCell;
+
+use num_bigint::BigInt;
+use serde::Serialize;
+use sha2::{Digest, Sha256};
+
+use crate::fingerprint;
+use crate::span::Span;
+
+/// How secrets are displayed.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub enum Policy {
+    /// Only the kind of a secret is displayed.
+    #[default]
+    Redacted,
+    /// The kind and the length of a secret are displayed.
+    Length,
+    /// SHA-256 of `salt` followed by a secret is displayed.
+    ///
+    /// Secrets are hashed in a canonical form: integers are hashed as their minimal big-endian
+    /// magnitude, so the same secret yields the same commitment across file formats.
+    Commitment { salt: Vec<u8> },
+    /// Secrets are displayed as is.
+    Reveal,
+}
+
+thread_local! {
+    /// The policy of the [`to_json`] that is running on this thread, since serde has no way to
+    /// pass it down to [`Secret`].
+    static POLICY: RefCell<Policy> = const { RefCell::new(Policy::Redacted) };
+}
+
+/// Serializes `value` into JSON, displaying its secrets according to `policy`.
+pub fn to_json<T: Serialize + ?Sized>(
+    value: &T,
+    policy: &Policy,
+) -> serde_json::Result<serde_json::Value> {
+    /// Restores the previous policy, even if serialization panics.
+    struct Restore(Option<Policy>);
+
+    impl Drop for Restore {
+        fn drop(&mut self) {
+            if let Some(policy) = self.0.take() {
+                POLICY.with(|current| *current.borrow_mut() = policy);
+            }
+        }
+    }
+
+    let _restore = Restore(Some(POLICY.with(|current| current.replace(policy.clone()))));
+    serde_json::to_value(value)
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Kind {
+    Integer,
+    Bytes,
+    Text,
+}
+
+/// A secret value. It is displayed according to the policy of [`to_json`].
+pub struct Secret {
+    kind: Kind,
+    value: Vec<u8>,
+    span: Span,
+}
+
+impl Secret {
+    /// Creates a secret integer. Negative values are stored as their magnitude.
+    pub fn integer(value: &BigInt, span: Span) -> Self {
+        let (_, mut value) = value.to_bytes_be();
+        if value == [0] {
+            value.clear();
+        }
+        Self {
+            kind: Kind::Integer,
+            value,
+            span,
+        }
+    }
+
+    /// Creates a secret byte string.
+    pub fn bytes(value: &[u8], span: Span) -> Self {
+        Self {
+            kind: Kind::Bytes,
+            value: value.to_vec(),
+            span,
+        }
+    }
+
+    /// Creates a secret string, such as a password.
+    pub fn text(value: &str, span: Span) -> Self {
+        Self {
+            kind: Kind::Text,
+            value: value.as_bytes().to_vec(),
+            span,
+        }
+    }
+
+    /// Returns the secret in its canonical form: the minimal big-endian magnitude for integers.
+    pub fn expose(&self) -> &[u8] {
+        &self.value
+    }
+
+    fn display(&self, policy: &Policy) -> String {
+        let kind = match self.kind {
+            Kind::Integer => "integer",
+            Kind::Bytes => "bytes",
+            Kind::Text => "text",
+        };
+        match policy {
+            Policy::Redacted => format!("(PRIVATE {})", kind),
+            Policy::Length => format!("(PRIVATE {}: {} bytes)", kind, self.value.len()),
+            Policy::Commitment { salt } => {
+                let mut hasher = Sha256::new();
+                hasher.update(salt);
+                hasher.update(&self.value);
+                format!(
+                    "(PRIVATE {}: sha256:{})",
+                    kind,
+                    fingerprint::hex(&hasher.finalize())
+                )
+            }
+            Policy::Reveal => match self.kind {
+                Kind::Integer if self.value.is_empty() => "0x0".to_string(),
+                Kind::Integer => format!(
+                    "0x{}",
+                    fingerprint::hex(&self.value).trim_start_matches('0')
+                ),
+                Kind::Bytes => fingerprint::hex(&self.value),
+                Kind::Text => String::from_utf8_lossy(&self.value).into_owned(),
+            },
+        }
+    }
+}
+
+impl Serialize for Secret {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: serde::Serializer,
+    {
+        #[derive(Serialize)]
+        struct Displayed {
+            value: String,
+            span: Span,
+        }
+        Displayed {
+            value: POLICY.with(|policy| self.display(&policy.borrow())),
+            span: self.span,
+        }
+        .serialize(serializer)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn display_test() {
+        let secret = Secret::integer(&BigInt::from(0x1234), Span::new(0, 0));
+        assert_eq!(secret.display(&Policy::Redacted), "(PRIVATE integer)");
+        assert_eq!(
+            secret.display(&Policy::Length),
+            "(PRIVATE integer: 2 bytes)"
+        );
+        assert_eq!(secret.display(&Policy::Reveal), "0x1234");
+        // printf '\x12\x34' | sha256sum
+        assert_eq!(
+            secret.display(&Policy::Commitment { salt: vec![] }),
+            "(PRIVATE integer: sha256:3a103a4e5729ad68c02a678ae39accfbc0ae208096437401b7ceab63cca0622f)",
+        );
+        let secret = Secret::bytes(&[0x00, 0xab], Span::new(0, 0));
+        assert_eq!(secret.display(&Policy::Reveal), "00ab");
+        let secret = Secret::text("password", Span::new(0, 0));
+        assert_eq!(secret.display(&Policy::Redacted), "(PRIVATE text)");
+        assert_eq!(secret.display(&Policy::Reveal), "password");
+    }
+
+    #[test]
+    fn to_json_test() {
+        let secrets = vec![Secret::bytes(&[0xab], Span::new(0, 1))];
+        let value = to_json(&secrets, &Policy::Reveal).unwrap();
+        assert_eq!(value[0]["value"], "ab");
+        // The policy only applies within `to_json`.
+        let value = serde_json::to_value(&secrets).unwrap();
+        assert_eq!(value[0]["value"], "(PRIVATE bytes)");
+    }
+}
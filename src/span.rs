@@ -1,3 +1,5 @@
+use std::fmt;
+
 use serde::Serialize;
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
@@ -25,3 +27,37 @@ impl Span {
         self.start == self.end
     }
 }
+
+/// A value along with the location of its encoding, displayed as `{value, span}` like
+/// [`crate::int::DisplayedInt`].
+#[derive(Serialize)]
+pub struct Spanned<T> {
+    pub value: T,
+    pub span: Span,
+}
+
+impl<T> Spanned<T> {
+    pub fn new(value: T, span: Span) -> Self {
+        Self { value, span }
+    }
+}
+
+/// A value that may be omitted from the encoding for its DEFAULT, in which case it has no span.
+#[derive(Serialize)]
+pub struct Defaulted<T> {
+    pub value: T,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub span: Option<Span>,
+}
+
+impl<T> Defaulted<T> {
+    pub fn new(value: T, span: Option<Span>) -> Self {
+        Self { value, span }
+    }
+}
+
+impl fmt::Display for Span {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}..{}", self.start, self.end)
+    }
+}
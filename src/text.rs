@@ -0,0 +1,153 @@
+//! Human-readable rendering of displayed values.
+//!
+//! Everything clavem displays is first converted to a [`serde_json::Value`], so this module
+//! renders that tree as an indented list of fields, similarly to `openssl x509 -text`.
+use std::fmt::Write;
+
+use serde_json::{Map, Value};
+
+const INDENT: &str = "  ";
+
+/// Renders `value` as an indented tree.
+///
+/// If a node carries a `span` field, its byte range is printed next to the field name
+/// instead of being displayed as a child.
+pub fn render(value: &Value) -> String {
+    let mut out = String::new();
+    match value {
+        Value::Object(map) => render_fields(&mut out, map, 0),
+        Value::Array(array) => render_items(&mut out, array, 0),
+        _ => writeln!(out, "{}", scalar(value)).unwrap(),
+    }
+    out
+}
+
+fn render_fields(out: &mut String, map: &Map<String, Value>, depth: usize) {
+    for (key, value) in map {
+        if key == "span" && span(value).is_some() {
+            continue;
+        }
+        render_node(out, key, value, depth);
+    }
+}
+
+fn render_items(out: &mut String, array: &[Value], depth: usize) {
+    for (i, value) in array.iter().enumerate() {
+        render_node(out, &format!("[{}]", i), value, depth);
+    }
+}
+
+fn render_node(out: &mut String, label: &str, value: &Value, depth: usize) {
+    let indent = INDENT.repeat(depth);
+    match value {
+        Value::Object(map) => {
+            let span = map.get("span").and_then(span);
+            let suffix = span.map_or(String::new(), |(start, end)| {
+                format!(" [{}..{}]", start, end)
+            });
+            // A scalar annotated with a span only, e.g. `DisplayedInt`
+            let is_leaf = map.len() == 1 + usize::from(span.is_some())
+                && map.get("value").is_some_and(is_scalar);
+            if is_leaf {
+                writeln!(
+                    out,
+                    "{}{}: {}{}",
+                    indent,
+                    label,
+                    scalar(&map["value"]),
+                    suffix
+                )
+                .unwrap();
+            } else if map.is_empty() {
+                writeln!(out, "{}{}: (empty)", indent, label).unwrap();
+            } else {
+                writeln!(out, "{}{}:{}", indent, label, suffix).unwrap();
+                render_fields(out, map, depth + 1);
+            }
+        }
+        Value::Array(array) => {
+            if array.is_empty() {
+                writeln!(out, "{}{}: (empty)", indent, label).unwrap();
+            } else {
+                writeln!(out, "{}{}:", indent, label).unwrap();
+                render_items(out, array, depth + 1);
+            }
+        }
+        _ => writeln!(out, "{}{}: {}", indent, label, scalar(value)).unwrap(),
+    }
+}
+
+fn is_scalar(value: &Value) -> bool {
+    !matches!(value, Value::Object(_) | Value::Array(_))
+}
+
+fn scalar(value: &Value) -> String {
+    match value {
+        Value::Null => "(none)".to_string(),
+        Value::String(s) => s.clone(),
+        _ => value.to_string(),
+    }
+}
+
+/// Extracts `(start, end)` from a serialized [`crate::span::Span`].
+fn span(value: &Value) -> Option<(u64, u64)> {
+    let map = value.as_object()?;
+    if map.len() != 2 {
+        return None;
+    }
+    Some((map.get("start")?.as_u64()?, map.get("end")?.as_u64()?))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn render_test_nested() {
+        let value = json!({
+            "type": "PEM public key",
+            "value": {
+                "algorithm": "rsaEncryption (1.2.840.113549.1.1.1)",
+                "public_key": {
+                    "modulus": { "value": "(integer: 256 bytes)", "span": { "start": 4, "end": 265 } },
+                    "exponent": { "value": "0x10001", "span": { "start": 265, "end": 270 } },
+                },
+            },
+        });
+        let expected = "\
+type: PEM public key
+value:
+  algorithm: rsaEncryption (1.2.840.113549.1.1.1)
+  public_key:
+    modulus: (integer: 256 bytes) [4..265]
+    exponent: 0x10001 [265..270]
+";
+        assert_eq!(render(&value), expected);
+    }
+
+    #[test]
+    fn render_test_arrays_and_spans() {
+        let value = json!({
+            "pub_part": {
+                "algo": "ssh-ed25519",
+                "content": "(bitstring: 32 bytes)",
+                "span": { "start": 0, "end": 51 },
+            },
+            "subject": [[{ "type": "commonName (2.5.4.3)", "value": null }]],
+            "reserved": [],
+        });
+        let expected = "\
+pub_part: [0..51]
+  algo: ssh-ed25519
+  content: (bitstring: 32 bytes)
+subject:
+  [0]:
+    [0]:
+      type: commonName (2.5.4.3)
+      value: (none)
+reserved: (empty)
+";
+        assert_eq!(render(&value), expected);
+    }
+}
@@ -0,0 +1,141 @@
+//! Conversions between UNIX timestamps, ISO-8601 strings and durations, and the statuses of
+//! certificates at a point in time.
+//!
+//! Only UTC is supported, which is all certificates need.
+use serde::Serialize;
+
+/// Status of a certificate at a point in time, in increasing order of severity.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum Status {
+    Valid,
+    /// Valid, but expires within the given duration.
+    ExpiringSoon,
+    NotYetValid,
+    Expired,
+}
+
+/// Days since 1970-01-01 of a date in the proleptic Gregorian calendar.
+///
+/// Algorithm from <http://howardhinnant.github.io/date_algorithms.html#days_from_civil>.
+pub fn days_from_civil(year: i64, month: u32, day: u32) -> i64 {
+    let year = if month <= 2 { year - 1 } else { year };
+    let era = year.div_euclid(400);
+    let yoe = year - era * 400;
+    let month = i64::from(month);
+    let doy = (153 * (if month > 2 { month - 3 } else { month + 9 }) + 2) / 5 + i64::from(day) - 1;
+    let doe = yoe * 365 + yoe / 4 - yoe / 100 + doy;
+    era * 146097 + doe - 719468
+}
+
+/// Inverse of [`days_from_civil`].
+fn civil_from_days(days: i64) -> (i64, u32, u32) {
+    let days = days + 719468;
+    let era = days.div_euclid(146097);
+    let doe = days - era * 146097;
+    let yoe = (doe - doe / 1460 + doe / 36524 - doe / 146096) / 365;
+    let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+    let mp = (5 * doy + 2) / 153;
+    let day = (doy - (153 * mp + 2) / 5 + 1) as u32;
+    let month = if mp < 10 { mp + 3 } else { mp - 9 } as u32;
+    let year = yoe + era * 400 + i64::from(month <= 2);
+    (year, month, day)
+}
+
+/// Formats a UNIX timestamp as `YYYY-MM-DDTHH:MM:SSZ`.
+pub fn to_iso8601(timestamp: i64) -> String {
+    let (year, month, day) = civil_from_days(timestamp.div_euclid(86400));
+    let secs = timestamp.rem_euclid(86400);
+    format!(
+        "{:04}-{:02}-{:02}T{:02}:{:02}:{:02}Z",
+        year,
+        month,
+        day,
+        secs / 3600,
+        secs / 60 % 60,
+        secs % 60,
+    )
+}
+
+/// Parses `YYYY-MM-DD`, `YYYY-MM-DDTHH:MM:SSZ` or `@` followed by a UNIX timestamp.
+pub fn parse_iso8601(s: &str) -> Option<i64> {
+    if let Some(timestamp) = s.strip_prefix('@') {
+        return timestamp.parse().ok();
+    }
+    let (date, time) = match s.split_once('T') {
+        Some((date, time)) => (date, Some(time.strip_suffix('Z')?)),
+        None => (s, None),
+    };
+    let fields = |s: &str, sep| -> Option<Vec<u32>> {
+        s.split(sep)
+            .map(|field| {
+                if field.is_empty() || !field.bytes().all(|b| b.is_ascii_digit()) {
+                    return None;
+                }
+                field.parse().ok()
+            })
+            .collect()
+    };
+    let &[year, month, day] = fields(date, '-')?.as_slice() else {
+        return None;
+    };
+    if !(1..=12).contains(&month) || !(1..=31).contains(&day) {
+        return None;
+    }
+    let (hour, minute, second) = match time {
+        Some(time) => {
+            let &[hour, minute, second] = fields(time, ':')?.as_slice() else {
+                return None;
+            };
+            (hour, minute, second)
+        }
+        None => (0, 0, 0),
+    };
+    if hour > 23 || minute > 59 || second > 60 {
+        return None;
+    }
+    let days = days_from_civil(i64::from(year), month, day);
+    Some(days * 86400 + i64::from(hour * 3600 + minute * 60 + second))
+}
+
+/// Parses a duration such as `30d`, `12h`, `15m` or `90s` into seconds. Plain numbers are seconds.
+pub fn parse_duration(s: &str) -> Option<i64> {
+    let (number, unit) = match s.find(|c: char| !c.is_ascii_digit()) {
+        Some(pos) => s.split_at(pos),
+        None => (s, "s"),
+    };
+    let unit = match unit {
+        "s" => 1,
+        "m" => 60,
+        "h" => 3600,
+        "d" => 86400,
+        "w" => 7 * 86400,
+        _ => return None,
+    };
+    number.parse::<i64>().ok()?.checked_mul(unit)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn iso8601_test() {
+        assert_eq!(to_iso8601(0), "1970-01-01T00:00:00Z");
+        assert_eq!(to_iso8601(951825600), "2000-02-29T12:00:00Z");
+        assert_eq!(to_iso8601(-1), "1969-12-31T23:59:59Z");
+        assert_eq!(parse_iso8601("2000-02-29T12:00:00Z"), Some(951825600));
+        assert_eq!(parse_iso8601("2000-02-29"), Some(951782400));
+        assert_eq!(parse_iso8601("@951825600"), Some(951825600));
+        assert_eq!(parse_iso8601("2000-02-29T12:00:00"), None);
+        assert_eq!(parse_iso8601("2000-13-01"), None);
+    }
+
+    #[test]
+    fn parse_duration_test() {
+        assert_eq!(parse_duration("30d"), Some(30 * 86400));
+        assert_eq!(parse_duration("90"), Some(90));
+        assert_eq!(parse_duration("1y"), None);
+        assert_eq!(parse_duration("d"), None);
+    }
+}
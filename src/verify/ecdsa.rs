@@ -0,0 +1,265 @@
+//! ECDSA on the NIST curves P-256 and P-384 ([FIPS 186-5](https://doi.org/10.6028/NIST.FIPS.186-5)
+//! Section 6.4.2).
+use asn1_rs::{DerSequence, FromDer, Integer, Oid};
+use num_bigint::BigUint;
+
+use super::field::{self, Field};
+
+/// A short Weierstrass curve y² = x³ - 3x + b, with coefficients in hex.
+pub(crate) struct Curve {
+    p: &'static str,
+    b: &'static str,
+    gx: &'static str,
+    gy: &'static str,
+    n: &'static str,
+}
+
+pub(crate) const P256: Curve = Curve {
+    p: "ffffffff00000001000000000000000000000000ffffffffffffffffffffffff",
+    b: "5ac635d8aa3a93e7b3ebbd55769886bc651d06b0cc53b0f63bce3c3e27d2604b",
+    gx: "6b17d1f2e12c4247f8bce6e563a440f277037d812deb33a0f4a13945d898c296",
+    gy: "4fe342e2fe1a7f9b8ee7eb4a7c0f9e162bce33576b315ececbb6406837bf51f5",
+    n: "ffffffff00000000ffffffffffffffffbce6faada7179e84f3b9cac2fc632551",
+};
+
+pub(crate) const P384: Curve = Curve {
+    p: "fffffffffffffffffffffffffffffffffffffffffffffffffffffffffffffffeffffffff0000000000000000ffffffff",
+    b: "b3312fa7e23ee7e4988e056be3f82d19181d9c6efe8141120314088f5013875ac656398d8a2ed19d2a85c8edd3ec2aef",
+    gx: "aa87ca22be8b05378eb1c71ef320ad746e1d3b628ba79b9859f741e082542a385502f25dbf55296c3a545e3872760ab7",
+    gy: "3617de4a96262c6f5d9e98bf9292dc29f8f41dbd289a147ce9da3113b5f0b8c00a60b1ce1d7e819d7a431d7c90ea0e5f",
+    n: "ffffffffffffffffffffffffffffffffffffffffffffffffc7634d81f4372ddf581a0db248b0a77aecec196accc52973",
+};
+
+impl Curve {
+    /// Returns the curve identified by `oid` in ECParameters (RFC 5480 Section 2.1.1.1).
+    pub(crate) fn from_oid(oid: &Oid) -> Option<&'static Curve> {
+        if *oid == asn1_rs::oid!(1.2.840 .10045 .3 .1 .7) {
+            Some(&P256)
+        } else if *oid == asn1_rs::oid!(1.3.132 .0 .34) {
+            Some(&P384)
+        } else {
+            None
+        }
+    }
+}
+
+/// A point in Jacobian coordinates, (X/Z², Y/Z³). The point at infinity has Z = 0.
+#[derive(Clone)]
+struct Point {
+    x: BigUint,
+    y: BigUint,
+    z: BigUint,
+}
+
+struct Group {
+    f: Field,
+    b: BigUint,
+    n: BigUint,
+}
+
+impl Group {
+    fn new(curve: &Curve) -> Self {
+        Self {
+            f: Field::new(field::hex(curve.p)),
+            b: field::hex(curve.b),
+            n: field::hex(curve.n),
+        }
+    }
+
+    fn infinity() -> Point {
+        Point {
+            x: BigUint::from(1u32),
+            y: BigUint::from(1u32),
+            z: BigUint::default(),
+        }
+    }
+
+    fn affine(x: BigUint, y: BigUint) -> Point {
+        Point {
+            x,
+            y,
+            z: BigUint::from(1u32),
+        }
+    }
+
+    /// Returns x³ - 3x + b.
+    fn rhs(&self, x: &BigUint) -> BigUint {
+        let f = &self.f;
+        let x3 = f.mul(&f.mul(x, x), x);
+        let three_x = f.mul(&BigUint::from(3u32), x);
+        f.add(&f.sub(&x3, &three_x), &self.b)
+    }
+
+    /// Decodes an uncompressed or compressed point (SEC 1 Section 2.3.4).
+    fn decode(&self, bytes: &[u8]) -> Option<Point> {
+        let len = ((self.f.p.bits() + 7) / 8) as usize;
+        let (&first, rest) = bytes.split_first()?;
+        match first {
+            0x04 if rest.len() == 2 * len => {
+                let x = BigUint::from_bytes_be(&rest[..len]);
+                let y = BigUint::from_bytes_be(&rest[len..]);
+                if x >= self.f.p || y >= self.f.p || self.f.mul(&y, &y) != self.rhs(&x) {
+                    return None;
+                }
+                Some(Self::affine(x, y))
+            }
+            0x02 | 0x03 if rest.len() == len => {
+                let x = BigUint::from_bytes_be(rest);
+                if x >= self.f.p {
+                    return None;
+                }
+                let mut y = self.f.sqrt(&self.rhs(&x))?;
+                if y.bit(0) != (first == 0x03) {
+                    y = self.f.neg(&y);
+                }
+                Some(Self::affine(x, y))
+            }
+            _ => None,
+        }
+    }
+
+    // https://hyperelliptic.org/EFD/g1p/auto-shortw-jacobian-3.html#doubling-dbl-2001-b
+    fn double(&self, p: &Point) -> Point {
+        let f = &self.f;
+        if p.z == BigUint::default() || p.y == BigUint::default() {
+            return Self::infinity();
+        }
+        let delta = f.mul(&p.z, &p.z);
+        let gamma = f.mul(&p.y, &p.y);
+        let beta = f.mul(&p.x, &gamma);
+        let alpha = f.mul(
+            &BigUint::from(3u32),
+            &f.mul(&f.sub(&p.x, &delta), &f.add(&p.x, &delta)),
+        );
+        let eight_beta = f.mul(&BigUint::from(8u32), &beta);
+        let x = f.sub(&f.mul(&alpha, &alpha), &eight_beta);
+        let y_plus_z = f.add(&p.y, &p.z);
+        let z = f.sub(&f.sub(&f.mul(&y_plus_z, &y_plus_z), &gamma), &delta);
+        let four_beta = f.mul(&BigUint::from(4u32), &beta);
+        let eight_gamma2 = f.mul(&BigUint::from(8u32), &f.mul(&gamma, &gamma));
+        let y = f.sub(&f.mul(&alpha, &f.sub(&four_beta, &x)), &eight_gamma2);
+        Point { x, y, z }
+    }
+
+    // https://hyperelliptic.org/EFD/g1p/auto-shortw-jacobian-3.html#addition-add-2007-bl
+    fn add(&self, p: &Point, q: &Point) -> Point {
+        let f = &self.f;
+        if p.z == BigUint::default() {
+            return q.clone();
+        }
+        if q.z == BigUint::default() {
+            return p.clone();
+        }
+        let z1z1 = f.mul(&p.z, &p.z);
+        let z2z2 = f.mul(&q.z, &q.z);
+        let u1 = f.mul(&p.x, &z2z2);
+        let u2 = f.mul(&q.x, &z1z1);
+        let s1 = f.mul(&f.mul(&p.y, &q.z), &z2z2);
+        let s2 = f.mul(&f.mul(&q.y, &p.z), &z1z1);
+        let h = f.sub(&u2, &u1);
+        let r = f.add(&f.sub(&s2, &s1), &f.sub(&s2, &s1));
+        if h == BigUint::default() {
+            return if r == BigUint::default() {
+                self.double(p)
+            } else {
+                Self::infinity()
+            };
+        }
+        let two_h = f.add(&h, &h);
+        let i = f.mul(&two_h, &two_h);
+        let j = f.mul(&h, &i);
+        let v = f.mul(&u1, &i);
+        let x = f.sub(&f.sub(&f.mul(&r, &r), &j), &f.add(&v, &v));
+        let s1j = f.mul(&s1, &j);
+        let y = f.sub(&f.mul(&r, &f.sub(&v, &x)), &f.add(&s1j, &s1j));
+        let z1_plus_z2 = f.add(&p.z, &q.z);
+        let z = f.mul(
+            &f.sub(&f.sub(&f.mul(&z1_plus_z2, &z1_plus_z2), &z1z1), &z2z2),
+            &h,
+        );
+        Point { x, y, z }
+    }
+
+    fn mul(&self, k: &BigUint, p: &Point) -> Point {
+        let mut result = Self::infinity();
+        for i in (0..k.bits()).rev() {
+            result = self.double(&result);
+            if k.bit(i) {
+                result = self.add(&result, p);
+            }
+        }
+        result
+    }
+}
+
+/// Returns whether the encoded point `point` is the public key of the private key `scalar`
+/// (SEC 1 Section 3.2.1), or `None` if either is malformed.
+pub(super) fn public_key_matches(curve: &Curve, scalar: &[u8], point: &[u8]) -> Option<bool> {
+    let group = Group::new(curve);
+    let q = group.decode(point)?;
+    let d = BigUint::from_bytes_be(scalar);
+    if d == BigUint::default() || d >= group.n {
+        return None;
+    }
+    let g = Group::affine(field::hex(curve.gx), field::hex(curve.gy));
+    let derived = group.mul(&d, &g);
+    // Both are compared in affine coordinates; q has Z = 1.
+    let f = &group.f;
+    let z2 = f.mul(&derived.z, &derived.z);
+    let z3 = f.mul(&z2, &derived.z);
+    Some(derived.x == f.mul(&q.x, &z2) && derived.y == f.mul(&q.y, &z3))
+}
+
+// https://datatracker.ietf.org/doc/html/rfc3279#section-2.2.3
+#[derive(DerSequence)]
+struct EcdsaSigValueAsn1<'a> {
+    r: Integer<'a>,
+    s: Integer<'a>,
+}
+
+/// Verifies the DER signature `signature` of the message whose hash is `hash` with the
+/// encoded point `point`.
+pub(super) fn verify(
+    curve: &Curve,
+    point: &[u8],
+    hash: &[u8],
+    signature: &[u8],
+) -> Result<(), &'static str> {
+    let group = Group::new(curve);
+    let q = group.decode(point).ok_or("invalid public key")?;
+    let (rem, sig) = EcdsaSigValueAsn1::from_der(signature).map_err(|_| "malformed signature")?;
+    if !rem.is_empty() {
+        return Err("malformed signature");
+    }
+    let (r, s) = match (sig.r.as_biguint(), sig.s.as_biguint()) {
+        (Ok(r), Ok(s)) => (r, s),
+        _ => return Err("malformed signature"),
+    };
+    let n = &group.n;
+    let zero = BigUint::default();
+    if r == zero || s == zero || r >= *n || s >= *n {
+        return Err("signature out of range");
+    }
+    // The leftmost bits of the hash, as many as n has.
+    let mut e = BigUint::from_bytes_be(hash);
+    let hash_bits = hash.len() as u64 * 8;
+    if hash_bits > n.bits() {
+        e >>= hash_bits - n.bits();
+    }
+    let w = s.modpow(&(n - 2u32), n);
+    let u1 = e * &w % n;
+    let u2 = &r * &w % n;
+    let g = Group::affine(field::hex(curve.gx), field::hex(curve.gy));
+    let sum = group.add(&group.mul(&u1, &g), &group.mul(&u2, &q));
+    if sum.z == zero {
+        return Err("signature mismatch");
+    }
+    let f = &group.f;
+    let z2 = f.mul(&sum.z, &sum.z);
+    let x = f.mul(&sum.x, &f.inv(&z2));
+    if x % n == r {
+        Ok(())
+    } else {
+        Err("signature mismatch")
+    }
+}
@@ -0,0 +1,281 @@
+//! Ed25519 and Ed448 ([RFC 8032](https://datatracker.ietf.org/doc/html/rfc8032)).
+use num_bigint::BigUint;
+use sha2::{Digest, Sha512};
+use sha3::digest::{ExtendableOutput, Update, XofReader};
+use sha3::Shake256;
+
+use super::field::{self, Field};
+
+/// A twisted Edwards curve ax² + y² = 1 + dx²y², with parameters in hex.
+pub(crate) struct Curve {
+    p: &'static str,
+    a: &'static str,
+    d: &'static str,
+    l: &'static str,
+    bx: &'static str,
+    by: &'static str,
+    /// Length of encoded points and scalars.
+    len: usize,
+}
+
+// RFC 8032 Section 5.1
+pub(crate) const ED25519: Curve = Curve {
+    p: "7fffffffffffffffffffffffffffffffffffffffffffffffffffffffffffffed",
+    a: "7fffffffffffffffffffffffffffffffffffffffffffffffffffffffffffffec",
+    d: "52036cee2b6ffe738cc740797779e89800700a4d4141d8ab75eb4dca135978a3",
+    l: "1000000000000000000000000000000014def9dea2f79cd65812631a5cf5d3ed",
+    bx: "216936d3cd6e53fec0a4e231fdd6dc5c692cc7609525a7b2c9562d608f25d51a",
+    by: "6666666666666666666666666666666666666666666666666666666666666658",
+    len: 32,
+};
+
+// RFC 8032 Section 5.2
+pub(crate) const ED448: Curve = Curve {
+    p: "fffffffffffffffffffffffffffffffffffffffffffffffffffffffeffffffffffffffffffffffffffffffffffffffffffffffffffffffff",
+    a: "1",
+    d: "fffffffffffffffffffffffffffffffffffffffffffffffffffffffeffffffffffffffffffffffffffffffffffffffffffffffffffff6756",
+    l: "3fffffffffffffffffffffffffffffffffffffffffffffffffffffff7cca23e9c44edb49aed63690216cc2728dc58f552378c292ab5844f3",
+    bx: "4f1970c66bed0ded221d15a622bf36da9e146570470f1767ea6de324a3d3a46412ae1af72ab66511433b80e18b00938e2626a82bc70cc05e",
+    by: "693f46716eb6bc248876203756c9c7624bea73736ca3984087789c1e05a0c2d73ad3ff1ce67c39c4fdbd132c4ed7c8ad9808795bf230fa14",
+    len: 57,
+};
+
+/// A point in projective coordinates, (X/Z, Y/Z).
+#[derive(Clone)]
+struct Point {
+    x: BigUint,
+    y: BigUint,
+    z: BigUint,
+}
+
+struct Group {
+    f: Field,
+    a: BigUint,
+    d: BigUint,
+    len: usize,
+}
+
+impl Group {
+    fn new(curve: &Curve) -> Self {
+        Self {
+            f: Field::new(field::hex(curve.p)),
+            a: field::hex(curve.a),
+            d: field::hex(curve.d),
+            len: curve.len,
+        }
+    }
+
+    /// Decodes a point (RFC 8032 Sections 5.1.3 and 5.2.3).
+    fn decode(&self, bytes: &[u8]) -> Option<Point> {
+        let f = &self.f;
+        let (&last, _) = bytes.split_last()?;
+        if bytes.len() != self.len {
+            return None;
+        }
+        let sign = last >> 7 == 1;
+        let mut le = bytes.to_vec();
+        le[self.len - 1] &= 0x7f;
+        let y = BigUint::from_bytes_le(&le);
+        if y >= f.p {
+            return None;
+        }
+        // x² = (y² - 1) / (dy² - a)
+        let y2 = f.mul(&y, &y);
+        let u = f.sub(&y2, &BigUint::from(1u32));
+        let v = f.sub(&f.mul(&self.d, &y2), &self.a);
+        let mut x = f.sqrt(&f.mul(&u, &f.inv(&v)))?;
+        if x == BigUint::default() && sign {
+            return None;
+        }
+        if x.bit(0) != sign {
+            x = f.neg(&x);
+        }
+        Some(Point {
+            x,
+            y,
+            z: BigUint::from(1u32),
+        })
+    }
+
+    // https://hyperelliptic.org/EFD/g1p/auto-twisted-projective.html#addition-add-2008-bbjlp
+    // The formula is complete for both curves, so it is also used for doubling.
+    fn add(&self, p: &Point, q: &Point) -> Point {
+        let f = &self.f;
+        let a = f.mul(&p.z, &q.z);
+        let b = f.mul(&a, &a);
+        let c = f.mul(&p.x, &q.x);
+        let d = f.mul(&p.y, &q.y);
+        let e = f.mul(&self.d, &f.mul(&c, &d));
+        let ff = f.sub(&b, &e);
+        let g = f.add(&b, &e);
+        let cross = f.mul(&f.add(&p.x, &p.y), &f.add(&q.x, &q.y));
+        let x = f.mul(&f.mul(&a, &ff), &f.sub(&f.sub(&cross, &c), &d));
+        let y = f.mul(&f.mul(&a, &g), &f.sub(&d, &f.mul(&self.a, &c)));
+        let z = f.mul(&ff, &g);
+        Point { x, y, z }
+    }
+
+    fn mul(&self, k: &BigUint, p: &Point) -> Point {
+        let mut result = Point {
+            x: BigUint::default(),
+            y: BigUint::from(1u32),
+            z: BigUint::from(1u32),
+        };
+        for i in (0..k.bits()).rev() {
+            result = self.add(&result, &result);
+            if k.bit(i) {
+                result = self.add(&result, p);
+            }
+        }
+        result
+    }
+
+    /// Encodes a point (RFC 8032 Sections 5.1.2 and 5.2.2).
+    fn encode(&self, p: &Point) -> Vec<u8> {
+        let f = &self.f;
+        let z = f.inv(&p.z);
+        let (x, y) = (f.mul(&p.x, &z), f.mul(&p.y, &z));
+        let mut bytes = y.to_bytes_le();
+        bytes.resize(self.len, 0);
+        if x.bit(0) {
+            bytes[self.len - 1] |= 0x80;
+        }
+        bytes
+    }
+
+    fn eq(&self, p: &Point, q: &Point) -> bool {
+        let f = &self.f;
+        f.mul(&p.x, &q.z) == f.mul(&q.x, &p.z) && f.mul(&p.y, &q.z) == f.mul(&q.y, &p.z)
+    }
+}
+
+fn base_point(curve: &Curve) -> Point {
+    Point {
+        x: field::hex(curve.bx),
+        y: field::hex(curve.by),
+        z: BigUint::from(1u32),
+    }
+}
+
+/// Derives the encoded public key of the private key `seed` (RFC 8032 Sections 5.1.5 and 5.2.5).
+pub(super) fn public_key(curve: &Curve, seed: &[u8]) -> Option<Vec<u8>> {
+    let len = curve.len;
+    if seed.len() != len {
+        return None;
+    }
+    let mut h = if len == ED25519.len {
+        let mut h = Sha512::digest(seed).to_vec();
+        h[0] &= 0xf8;
+        h[31] = h[31] & 0x7f | 0x40;
+        h
+    } else {
+        let mut hasher = Shake256::default();
+        hasher.update(seed);
+        let mut h = vec![0; 2 * len];
+        hasher.finalize_xof().read(&mut h);
+        h[0] &= 0xfc;
+        h[55] |= 0x80;
+        h[56] = 0;
+        h
+    };
+    h.truncate(len);
+    let group = Group::new(curve);
+    let s = BigUint::from_bytes_le(&h);
+    Some(group.encode(&group.mul(&s, &base_point(curve))))
+}
+
+/// Verifies the signature `signature` of `message` with the encoded public key `public_key`.
+pub(super) fn verify(
+    curve: &Curve,
+    public_key: &[u8],
+    message: &[u8],
+    signature: &[u8],
+) -> Result<(), &'static str> {
+    let group = Group::new(curve);
+    let len = curve.len;
+    let a = group.decode(public_key).ok_or("invalid public key")?;
+    if signature.len() != 2 * len {
+        return Err("malformed signature");
+    }
+    let (r_bytes, s_bytes) = signature.split_at(len);
+    let r = group.decode(r_bytes).ok_or("malformed signature")?;
+    let s = BigUint::from_bytes_le(s_bytes);
+    let l = field::hex(curve.l);
+    if s >= l {
+        return Err("signature out of range");
+    }
+    let k = if len == ED25519.len {
+        let mut hasher = Sha512::new();
+        Digest::update(&mut hasher, r_bytes);
+        Digest::update(&mut hasher, public_key);
+        Digest::update(&mut hasher, message);
+        BigUint::from_bytes_le(&hasher.finalize())
+    } else {
+        // dom4(0, "") of RFC 8032 Section 2, for pure Ed448 without context.
+        let mut hasher = Shake256::default();
+        hasher.update(b"SigEd448\0\0");
+        hasher.update(r_bytes);
+        hasher.update(public_key);
+        hasher.update(message);
+        let mut digest = [0; 114];
+        hasher.finalize_xof().read(&mut digest);
+        BigUint::from_bytes_le(&digest)
+    } % &l;
+    let b = base_point(curve);
+    // The cofactorless equation [S]B = R + [k]A.
+    let lhs = group.mul(&s, &b);
+    let rhs = group.add(&r, &group.mul(&k, &a));
+    if group.eq(&lhs, &rhs) {
+        Ok(())
+    } else {
+        Err("signature mismatch")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn unhex(s: &str) -> Vec<u8> {
+        (0..s.len())
+            .step_by(2)
+            .map(|i| u8::from_str_radix(&s[i..i + 2], 16).unwrap())
+            .collect()
+    }
+
+    #[test]
+    fn ed25519_test() {
+        // RFC 8032 Section 7.1, TEST 2
+        let public_key = unhex("3d4017c3e843895a92b70aa74d1b7ebc9c982ccf2ec4968cc0cd55f12af4660c");
+        let signature = unhex(concat!(
+            "92a009a9f0d4cab8720e820b5f642540a2b27b5416503f8fb3762223ebdb69da",
+            "085ac1e43e15996e458f3613d0f11d8c387b2eaeb4302aeeb00d291612bb0c00"
+        ));
+        assert_eq!(verify(&ED25519, &public_key, b"\x72", &signature), Ok(()));
+        assert!(verify(&ED25519, &public_key, b"\x73", &signature).is_err());
+        let seed = unhex("4ccd089b28ff96da9db6c346ec114e0f5b8a319f35aba624da8cf6ed4fb8a6fb");
+        assert_eq!(super::public_key(&ED25519, &seed), Some(public_key));
+    }
+
+    #[test]
+    fn ed448_test() {
+        // RFC 8032 Section 7.4, 1 octet
+        let public_key = unhex(concat!(
+            "43ba28f430cdff456ae531545f7ecd0ac834a55d9358c0372bfa0c6c6798c086",
+            "6aea01eb00742802b8438ea4cb82169c235160627b4c3a9480"
+        ));
+        let signature = unhex(concat!(
+            "26b8f91727bd62897af15e41eb43c377efb9c610d48f2335cb0bd0087810f435",
+            "2541b143c4b981b7e18f62de8ccdf633fc1bf037ab7cd779805e0dbcc0aae1cb",
+            "cee1afb2e027df36bc04dcecbf154336c19f0af7e0a6472905e799f1953d2a0f",
+            "f3348ab21aa4adafd1d234441cf807c03a00"
+        ));
+        assert_eq!(verify(&ED448, &public_key, b"\x03", &signature), Ok(()));
+        assert!(verify(&ED448, &public_key, b"\x04", &signature).is_err());
+        let seed = unhex(concat!(
+            "c4eab05d357007c632f3dbb48489924d552b08fe0c353a0d4a1f00acda2c463a",
+            "fbea67c5e8d2877c5e3bc397a659949ef8021e954e0a12274e"
+        ));
+        assert_eq!(super::public_key(&ED448, &seed), Some(public_key));
+    }
+}
@@ -0,0 +1,61 @@
+//! Arithmetic in prime fields.
+use num_bigint::BigUint;
+
+/// The integers modulo a prime `p`. All operands must be reduced.
+pub(super) struct Field {
+    pub(super) p: BigUint,
+}
+
+impl Field {
+    pub(super) fn new(p: BigUint) -> Self {
+        Self { p }
+    }
+
+    pub(super) fn add(&self, a: &BigUint, b: &BigUint) -> BigUint {
+        (a + b) % &self.p
+    }
+
+    pub(super) fn sub(&self, a: &BigUint, b: &BigUint) -> BigUint {
+        (a + &self.p - b) % &self.p
+    }
+
+    pub(super) fn mul(&self, a: &BigUint, b: &BigUint) -> BigUint {
+        a * b % &self.p
+    }
+
+    pub(super) fn neg(&self, a: &BigUint) -> BigUint {
+        (&self.p - a) % &self.p
+    }
+
+    pub(super) fn pow(&self, a: &BigUint, e: &BigUint) -> BigUint {
+        a.modpow(e, &self.p)
+    }
+
+    /// Inverse by Fermat's little theorem. The inverse of 0 is 0.
+    pub(super) fn inv(&self, a: &BigUint) -> BigUint {
+        self.pow(a, &(&self.p - 2u32))
+    }
+
+    /// A square root of `a`, if any, for `p` ≡ 3 (mod 4) or `p` ≡ 5 (mod 8).
+    pub(super) fn sqrt(&self, a: &BigUint) -> Option<BigUint> {
+        let p = &self.p;
+        let root = if p % 4u32 == BigUint::from(3u32) {
+            self.pow(a, &((p + 1u32) >> 2))
+        } else {
+            // RFC 8032 Section 5.1.3
+            let root = self.pow(a, &((p + 3u32) >> 3));
+            if self.mul(&root, &root) == *a {
+                root
+            } else {
+                let sqrt_minus_one = self.pow(&BigUint::from(2u32), &((p - 1u32) >> 2));
+                self.mul(&root, &sqrt_minus_one)
+            }
+        };
+        (self.mul(&root, &root) == *a).then_some(root)
+    }
+}
+
+/// Parses a big-endian hex constant.
+pub(super) fn hex(s: &str) -> BigUint {
+    BigUint::parse_bytes(s.as_bytes(), 16).expect("valid constant")
+}
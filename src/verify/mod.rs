@@ -0,0 +1,446 @@
+//! Verification of signatures made by issuers.
+//!
+//! The supported schemes are RSASSA-PKCS1-v1_5, RSASSA-PSS, ECDSA on P-256 and P-384, Ed25519
+//! and Ed448. They are implemented on top of `num-bigint` to keep the binary small, and are
+//! not constant-time, which does not matter since they process public data, except for the
+//! derivation of public keys in [`Key::matches_private_key`], whose input is a file the user
+//! already holds.
+use asn1_rs::{Any, Class, DerSequence, FromDer, Integer, Oid};
+use num_bigint::BigUint;
+use serde::Serialize;
+use sha2::Digest;
+
+use crate::der::algorithm::AlgorithmIdentifierAsn1;
+use crate::der::pubkey::SubjectPublicKeyInfoAsn1;
+use crate::document::{Document, Value};
+
+mod ecdsa;
+mod eddsa;
+mod field;
+mod rsa;
+
+/// Hash functions used in signatures.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum Hash {
+    Sha1,
+    Sha224,
+    Sha256,
+    Sha384,
+    Sha512,
+}
+
+fn digest<D: Digest>(parts: &[&[u8]]) -> Vec<u8> {
+    let mut hasher = D::new();
+    for part in parts {
+        hasher.update(part);
+    }
+    hasher.finalize().to_vec()
+}
+
+impl Hash {
+    fn from_oid(oid: &Oid) -> Option<Self> {
+        let hashes = [
+            (asn1_rs::oid!(1.3.14 .3 .2 .26), Hash::Sha1),
+            (asn1_rs::oid!(2.16.840 .1 .101 .3 .4 .2 .4), Hash::Sha224),
+            (asn1_rs::oid!(2.16.840 .1 .101 .3 .4 .2 .1), Hash::Sha256),
+            (asn1_rs::oid!(2.16.840 .1 .101 .3 .4 .2 .2), Hash::Sha384),
+            (asn1_rs::oid!(2.16.840 .1 .101 .3 .4 .2 .3), Hash::Sha512),
+        ];
+        hashes
+            .into_iter()
+            .find(|(known, _)| known == oid)
+            .map(|(_, hash)| hash)
+    }
+
+    /// Hash of the concatenation of `parts`.
+    fn digest(self, parts: &[&[u8]]) -> Vec<u8> {
+        match self {
+            Hash::Sha1 => digest::<sha1::Sha1>(parts),
+            Hash::Sha224 => digest::<sha2::Sha224>(parts),
+            Hash::Sha256 => digest::<sha2::Sha256>(parts),
+            Hash::Sha384 => digest::<sha2::Sha384>(parts),
+            Hash::Sha512 => digest::<sha2::Sha512>(parts),
+        }
+    }
+
+    fn len(self) -> usize {
+        match self {
+            Hash::Sha1 => 20,
+            Hash::Sha224 => 28,
+            Hash::Sha256 => 32,
+            Hash::Sha384 => 48,
+            Hash::Sha512 => 64,
+        }
+    }
+
+    /// DER of DigestInfo without the digest (RFC 8017 Section 9.2, Note 1).
+    fn digest_info_prefix(self) -> &'static [u8] {
+        match self {
+            Hash::Sha1 => b"\x30\x21\x30\x09\x06\x05\x2b\x0e\x03\x02\x1a\x05\x00\x04\x14",
+            Hash::Sha224 => {
+                b"\x30\x2d\x30\x0d\x06\x09\x60\x86\x48\x01\x65\x03\x04\x02\x04\x05\x00\x04\x1c"
+            }
+            Hash::Sha256 => {
+                b"\x30\x31\x30\x0d\x06\x09\x60\x86\x48\x01\x65\x03\x04\x02\x01\x05\x00\x04\x20"
+            }
+            Hash::Sha384 => {
+                b"\x30\x41\x30\x0d\x06\x09\x60\x86\x48\x01\x65\x03\x04\x02\x02\x05\x00\x04\x30"
+            }
+            Hash::Sha512 => {
+                b"\x30\x51\x30\x0d\x06\x09\x60\x86\x48\x01\x65\x03\x04\x02\x03\x05\x00\x04\x40"
+            }
+        }
+    }
+}
+
+/// Signature schemes along with their parameters.
+enum Scheme {
+    Pkcs1(Hash),
+    Pss(rsa::PssParams),
+    Ecdsa(Hash),
+    Ed25519,
+    Ed448,
+}
+
+impl Scheme {
+    /// Returns the scheme of the DER AlgorithmIdentifier `der`.
+    fn from_der(der: &[u8]) -> Result<Self, &'static str> {
+        let (_, algorithm) =
+            AlgorithmIdentifierAsn1::from_der(der).map_err(|_| "malformed algorithm")?;
+        let oid = &algorithm.algorithm;
+        let schemes = [
+            (
+                asn1_rs::oid!(1.2.840 .113549 .1 .1 .5),
+                Scheme::Pkcs1(Hash::Sha1),
+            ),
+            (
+                asn1_rs::oid!(1.2.840 .113549 .1 .1 .14),
+                Scheme::Pkcs1(Hash::Sha224),
+            ),
+            (
+                asn1_rs::oid!(1.2.840 .113549 .1 .1 .11),
+                Scheme::Pkcs1(Hash::Sha256),
+            ),
+            (
+                asn1_rs::oid!(1.2.840 .113549 .1 .1 .12),
+                Scheme::Pkcs1(Hash::Sha384),
+            ),
+            (
+                asn1_rs::oid!(1.2.840 .113549 .1 .1 .13),
+                Scheme::Pkcs1(Hash::Sha512),
+            ),
+            (
+                asn1_rs::oid!(1.2.840 .10045 .4 .1),
+                Scheme::Ecdsa(Hash::Sha1),
+            ),
+            (
+                asn1_rs::oid!(1.2.840 .10045 .4 .3 .1),
+                Scheme::Ecdsa(Hash::Sha224),
+            ),
+            (
+                asn1_rs::oid!(1.2.840 .10045 .4 .3 .2),
+                Scheme::Ecdsa(Hash::Sha256),
+            ),
+            (
+                asn1_rs::oid!(1.2.840 .10045 .4 .3 .3),
+                Scheme::Ecdsa(Hash::Sha384),
+            ),
+            (
+                asn1_rs::oid!(1.2.840 .10045 .4 .3 .4),
+                Scheme::Ecdsa(Hash::Sha512),
+            ),
+            (asn1_rs::oid!(1.3.101 .112), Scheme::Ed25519),
+            (asn1_rs::oid!(1.3.101 .113), Scheme::Ed448),
+        ];
+        if *oid == oid_registry::OID_PKCS1_RSASSAPSS {
+            return pss_params(algorithm.parameter.as_ref()).map(Scheme::Pss);
+        }
+        schemes
+            .into_iter()
+            .find(|(known, _)| known == oid)
+            .map(|(_, scheme)| scheme)
+            .ok_or("unsupported signature algorithm")
+    }
+}
+
+/// Parses RSASSA-PSS-params (RFC 4055 Section 3.1). Absent parameters take their defaults.
+fn pss_params(params: Option<&Any>) -> Result<rsa::PssParams, &'static str> {
+    let malformed = "malformed RSASSA-PSS parameters";
+    let mut result = rsa::PssParams {
+        hash: Hash::Sha1,
+        mgf_hash: Hash::Sha1,
+        salt_len: 20,
+    };
+    let Some(params) = params else {
+        return Ok(result);
+    };
+    let mut rem = params.data;
+    while !rem.is_empty() {
+        let (next, field) = Any::from_der(rem).map_err(|_| malformed)?;
+        rem = next;
+        if field.class() != Class::ContextSpecific {
+            return Err(malformed);
+        }
+        let (_, inner) = Any::from_der(field.data).map_err(|_| malformed)?;
+        match field.tag().0 {
+            0 => {
+                let hash = AlgorithmIdentifierAsn1::try_from(inner).map_err(|_| malformed)?;
+                result.hash = Hash::from_oid(&hash.algorithm).ok_or("unsupported hash")?;
+            }
+            1 => {
+                let mgf = AlgorithmIdentifierAsn1::try_from(inner).map_err(|_| malformed)?;
+                if mgf.algorithm != asn1_rs::oid!(1.2.840 .113549 .1 .1 .8) {
+                    return Err("unsupported mask generation function");
+                }
+                let hash = mgf.parameter.ok_or(malformed)?;
+                let hash = AlgorithmIdentifierAsn1::try_from(hash).map_err(|_| malformed)?;
+                result.mgf_hash = Hash::from_oid(&hash.algorithm).ok_or("unsupported hash")?;
+            }
+            2 => {
+                let salt_len = Integer::try_from(inner).map_err(|_| malformed)?;
+                result.salt_len = salt_len.as_u32().map_err(|_| malformed)? as usize;
+            }
+            3 => {
+                let trailer = Integer::try_from(inner).map_err(|_| malformed)?;
+                if trailer.as_u32() != Ok(1) {
+                    return Err("unsupported trailer field");
+                }
+            }
+            _ => return Err(malformed),
+        }
+    }
+    Ok(result)
+}
+
+enum Material {
+    Rsa {
+        n: BigUint,
+        e: BigUint,
+    },
+    Ec {
+        curve: &'static ecdsa::Curve,
+        point: Vec<u8>,
+    },
+    Ed25519(Vec<u8>),
+    Ed448(Vec<u8>),
+}
+
+/// A public key of an issuer.
+pub struct Key(Material);
+
+// https://datatracker.ietf.org/doc/html/rfc8017#appendix-A.1.1
+#[derive(DerSequence)]
+struct RsaPublicKeyAsn1<'a> {
+    modulus: Integer<'a>,
+    exponent: Integer<'a>,
+}
+
+/// The public components of an RSAPrivateKey, whose other components are ignored.
+// https://datatracker.ietf.org/doc/html/rfc8017#appendix-A.1.2
+#[derive(DerSequence)]
+struct RsaPrivateKeyAsn1<'a> {
+    _version: Integer<'a>,
+    modulus: Integer<'a>,
+    public_exponent: Integer<'a>,
+}
+
+impl Key {
+    /// Returns the key in the DER SubjectPublicKeyInfo `der`, if its algorithm is supported.
+    pub fn from_spki(der: &[u8]) -> Option<Self> {
+        let (_, spki) = SubjectPublicKeyInfoAsn1::from_der(der).ok()?;
+        Self::from_algorithm(&spki.algorithm.value, &spki.subjectPublicKey.data)
+    }
+
+    /// Returns the key `key` of the algorithm `algorithm`, encoded as in a
+    /// SubjectPublicKeyInfo, if the algorithm is supported.
+    pub(crate) fn from_algorithm(algorithm: &AlgorithmIdentifierAsn1, key: &[u8]) -> Option<Self> {
+        let parameter = algorithm.parameter.as_ref();
+        let algorithm = &algorithm.algorithm;
+        let key = key.to_vec();
+        let material = if *algorithm == oid_registry::OID_PKCS1_RSAENCRYPTION
+            || *algorithm == oid_registry::OID_PKCS1_RSASSAPSS
+        {
+            return Self::from_rsa_public_key(&key);
+        } else if *algorithm == oid_registry::OID_KEY_TYPE_EC_PUBLIC_KEY {
+            let curve = Oid::try_from(parameter?).ok()?;
+            Material::Ec {
+                curve: ecdsa::Curve::from_oid(&curve)?,
+                point: key,
+            }
+        } else if *algorithm == oid_registry::OID_SIG_ED25519 {
+            Material::Ed25519(key)
+        } else if *algorithm == oid_registry::OID_SIG_ED448 {
+            Material::Ed448(key)
+        } else {
+            return None;
+        };
+        Some(Key(material))
+    }
+
+    /// Returns whether the key belongs to the private key `private_key`, which is the seed of
+    /// EdDSA keys, the scalar of EC keys or the DER RSAPrivateKey of RSA keys. Returns `None` if
+    /// the public key cannot be derived for the algorithm.
+    pub(crate) fn matches_private_key(&self, private_key: &[u8]) -> Option<bool> {
+        match &self.0 {
+            Material::Ed25519(key) => {
+                Some(eddsa::public_key(&eddsa::ED25519, private_key)? == *key)
+            }
+            Material::Ed448(key) => Some(eddsa::public_key(&eddsa::ED448, private_key)? == *key),
+            Material::Ec { curve, point } => ecdsa::public_key_matches(curve, private_key, point),
+            Material::Rsa { n, e } => {
+                let (_, rsa) = RsaPrivateKeyAsn1::from_der(private_key).ok()?;
+                Some(
+                    rsa.modulus.as_biguint().ok()? == *n
+                        && rsa.public_exponent.as_biguint().ok()? == *e,
+                )
+            }
+        }
+    }
+
+    /// Returns the key in the DER RSAPublicKey (PKCS #1) `der`.
+    pub fn from_rsa_public_key(der: &[u8]) -> Option<Self> {
+        let (_, rsa) = RsaPublicKeyAsn1::from_der(der).ok()?;
+        Some(Key(Material::Rsa {
+            n: rsa.modulus.as_biguint().ok()?,
+            e: rsa.exponent.as_biguint().ok()?,
+        }))
+    }
+
+    /// Returns the key in the OpenSSH public key blob `blob`, if its algorithm is supported.
+    pub fn from_openssh(blob: &[u8]) -> Option<Self> {
+        let mut fields = vec![];
+        let mut rem = blob;
+        while !rem.is_empty() {
+            let len = u32::from_be_bytes(rem.get(..4)?.try_into().ok()?) as usize;
+            fields.push(rem.get(4..4 + len)?);
+            rem = &rem[4 + len..];
+        }
+        let material = match fields.as_slice() {
+            [b"ssh-rsa", e, n] => Material::Rsa {
+                n: BigUint::from_bytes_be(n),
+                e: BigUint::from_bytes_be(e),
+            },
+            [b"ssh-ed25519", key] => Material::Ed25519(key.to_vec()),
+            [b"ecdsa-sha2-nistp256", b"nistp256", point] => Material::Ec {
+                curve: &ecdsa::P256,
+                point: point.to_vec(),
+            },
+            [b"ecdsa-sha2-nistp384", b"nistp384", point] => Material::Ec {
+                curve: &ecdsa::P384,
+                point: point.to_vec(),
+            },
+            _ => return None,
+        };
+        Some(Key(material))
+    }
+
+    /// Returns the public key of `document`, which may be a public key, a certificate or a
+    /// certification request.
+    pub fn from_document(document: &Document) -> Option<Self> {
+        match &document.value {
+            Value::PublicKey(key) => Self::from_spki(&key.der),
+            Value::RsaPublicKey(_) => Self::from_rsa_public_key(&document.data),
+            Value::Certificate(cert) => Self::from_spki(&cert.tbs_certificate.subject_pki.der),
+            Value::CertificationRequest(csr) => {
+                Self::from_spki(&csr.certification_request_info.subject_pk_info.der)
+            }
+            #[cfg(feature = "openssh")]
+            Value::OpensshPublicKey(key) => Self::from_openssh(&key.blob),
+            _ => None,
+        }
+    }
+}
+
+/// Outcome of a verification.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum Outcome {
+    Valid,
+    Invalid,
+    /// The signature algorithm or the key is not supported.
+    Unsupported,
+}
+
+#[derive(Serialize)]
+pub struct Verification {
+    /// `self` for self-signed certificates, or the name of the file of the issuer.
+    pub issuer: String,
+    pub result: Outcome,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub reason: Option<&'static str>,
+}
+
+impl Verification {
+    /// The outcome for issuers whose key is not supported.
+    pub fn unsupported_key(issuer: &str) -> Self {
+        Self {
+            issuer: issuer.to_string(),
+            result: Outcome::Unsupported,
+            reason: Some("unsupported issuer key"),
+        }
+    }
+}
+
+/// Signed data, as found in certificates and certification requests.
+#[derive(Default)]
+pub struct Signed {
+    /// DER of the signed data.
+    pub(crate) message: Vec<u8>,
+    /// DER of the AlgorithmIdentifier of the signature.
+    pub(crate) algorithm: Vec<u8>,
+    pub(crate) signature: Vec<u8>,
+}
+
+impl Signed {
+    /// Returns whether the signature algorithm is one of the algorithms of `key`, or is not
+    /// supported.
+    pub fn algorithm_matches(&self, key: &Key) -> bool {
+        let Ok(scheme) = Scheme::from_der(&self.algorithm) else {
+            return true;
+        };
+        matches!(
+            (scheme, &key.0),
+            (Scheme::Pkcs1(_) | Scheme::Pss(_), Material::Rsa { .. })
+                | (Scheme::Ecdsa(_), Material::Ec { .. })
+                | (Scheme::Ed25519, Material::Ed25519(_))
+                | (Scheme::Ed448, Material::Ed448(_))
+        )
+    }
+
+    /// Verifies the signature with the key of the issuer named `issuer`.
+    pub fn verify(&self, key: &Key, issuer: &str) -> Verification {
+        let (result, reason) = match self.check(key) {
+            Ok(()) => (Outcome::Valid, None),
+            Err((outcome, reason)) => (outcome, Some(reason)),
+        };
+        Verification {
+            issuer: issuer.to_string(),
+            result,
+            reason,
+        }
+    }
+
+    fn check(&self, key: &Key) -> Result<(), (Outcome, &'static str)> {
+        let scheme = Scheme::from_der(&self.algorithm).map_err(|e| (Outcome::Unsupported, e))?;
+        let (message, signature) = (self.message.as_slice(), self.signature.as_slice());
+        match (&scheme, &key.0) {
+            (Scheme::Pkcs1(hash), Material::Rsa { n, e }) => {
+                rsa::verify_pkcs1(n, e, *hash, message, signature)
+            }
+            (Scheme::Pss(params), Material::Rsa { n, e }) => {
+                rsa::verify_pss(n, e, params, message, signature)
+            }
+            (Scheme::Ecdsa(hash), Material::Ec { curve, point }) => {
+                ecdsa::verify(curve, point, &hash.digest(&[message]), signature)
+            }
+            (Scheme::Ed25519, Material::Ed25519(key)) => {
+                eddsa::verify(&eddsa::ED25519, key, message, signature)
+            }
+            (Scheme::Ed448, Material::Ed448(key)) => {
+                eddsa::verify(&eddsa::ED448, key, message, signature)
+            }
+            _ => Err("the key does not match the signature algorithm"),
+        }
+        .map_err(|e| (Outcome::Invalid, e))
+    }
+}
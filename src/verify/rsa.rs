@@ -0,0 +1,154 @@
+//! RSASSA-PKCS1-v1_5 and RSASSA-PSS ([RFC 8017](https://datatracker.ietf.org/doc/html/rfc8017)
+//! Section 8).
+use num_bigint::BigUint;
+
+use super::Hash;
+
+/// Parameters of RSASSA-PSS (RFC 8017 Appendix A.2.3).
+pub(super) struct PssParams {
+    pub(super) hash: Hash,
+    pub(super) mgf_hash: Hash,
+    pub(super) salt_len: usize,
+}
+
+/// Checks that the modulus `n` is odd and larger than 1, as a product of odd primes is.
+fn check_modulus(n: &BigUint) -> Result<(), &'static str> {
+    if n.bits() < 2 || !n.bit(0) {
+        return Err("invalid modulus");
+    }
+    Ok(())
+}
+
+/// Returns the encoded message of `signature` (RFC 8017 Section 5.2.2), which is `len` bytes long.
+fn open(n: &BigUint, e: &BigUint, signature: &[u8], len: usize) -> Result<Vec<u8>, &'static str> {
+    check_modulus(n)?;
+    let k = ((n.bits() + 7) / 8) as usize;
+    if signature.len() != k {
+        return Err("signature length does not match the modulus");
+    }
+    let s = BigUint::from_bytes_be(signature);
+    if s >= *n {
+        return Err("signature out of range");
+    }
+    let m = s.modpow(e, n).to_bytes_be();
+    if m.len() > len {
+        return Err("signature mismatch");
+    }
+    let mut em = vec![0; len - m.len()];
+    em.extend_from_slice(&m);
+    Ok(em)
+}
+
+/// RFC 8017 Section 8.2.2
+pub(super) fn verify_pkcs1(
+    n: &BigUint,
+    e: &BigUint,
+    hash: Hash,
+    message: &[u8],
+    signature: &[u8],
+) -> Result<(), &'static str> {
+    let k = signature.len();
+    let em = open(n, e, signature, k)?;
+    // EMSA-PKCS1-v1_5 encoding (RFC 8017 Section 9.2)
+    let mut t = hash.digest_info_prefix().to_vec();
+    t.extend_from_slice(&hash.digest(&[message]));
+    if k < t.len() + 11 {
+        return Err("modulus too short");
+    }
+    let mut expected = vec![0x00, 0x01];
+    expected.resize(k - t.len() - 1, 0xff);
+    expected.push(0x00);
+    expected.extend_from_slice(&t);
+    if em == expected {
+        Ok(())
+    } else {
+        Err("signature mismatch")
+    }
+}
+
+/// MGF1 (RFC 8017 Appendix B.2.1)
+fn mgf1(hash: Hash, seed: &[u8], len: usize) -> Vec<u8> {
+    let mut mask = Vec::with_capacity(len);
+    let mut counter = 0u32;
+    while mask.len() < len {
+        mask.extend_from_slice(&hash.digest(&[seed, &counter.to_be_bytes()]));
+        counter += 1;
+    }
+    mask.truncate(len);
+    mask
+}
+
+/// RFC 8017 Section 8.1.2
+pub(super) fn verify_pss(
+    n: &BigUint,
+    e: &BigUint,
+    params: &PssParams,
+    message: &[u8],
+    signature: &[u8],
+) -> Result<(), &'static str> {
+    check_modulus(n)?;
+    let em_bits = (n.bits() - 1) as usize;
+    let em_len = (em_bits + 7) / 8;
+    if signature.len() > em_len {
+        // The modulus has one more byte than the encoded message, which must be zero.
+        let em = open(n, e, signature, signature.len())?;
+        if em[0] != 0 {
+            return Err("signature mismatch");
+        }
+    }
+    let em = open(n, e, signature, em_len)?;
+    // EMSA-PSS verification (RFC 8017 Section 9.1.2)
+    let h_len = params.hash.len();
+    let m_hash = params.hash.digest(&[message]);
+    if em_len < h_len + params.salt_len + 2 || em[em_len - 1] != 0xbc {
+        return Err("signature mismatch");
+    }
+    let (masked_db, h) = em[..em_len - 1].split_at(em_len - h_len - 1);
+    let unused_bits = 8 * em_len - em_bits;
+    let top_mask = 0xffu8.checked_shr(unused_bits as u32).unwrap_or(0);
+    if masked_db[0] & !top_mask != 0 {
+        return Err("signature mismatch");
+    }
+    let mut db: Vec<u8> = masked_db
+        .iter()
+        .zip(mgf1(params.mgf_hash, h, masked_db.len()))
+        .map(|(a, b)| a ^ b)
+        .collect();
+    db[0] &= top_mask;
+    let padding_len = em_len - h_len - params.salt_len - 2;
+    if db[..padding_len].iter().any(|&b| b != 0) || db[padding_len] != 0x01 {
+        return Err("signature mismatch");
+    }
+    let salt = &db[db.len() - params.salt_len..];
+    if params.hash.digest(&[&[0; 8], &m_hash, salt]) == h {
+        Ok(())
+    } else {
+        Err("signature mismatch")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn invalid_modulus_test() {
+        let params = PssParams {
+            hash: Hash::Sha256,
+            mgf_hash: Hash::Sha256,
+            salt_len: 32,
+        };
+        let e = BigUint::from(3u8);
+        for n in [0u32, 1, 3234] {
+            let n = BigUint::from(n);
+            assert_eq!(
+                verify_pss(&n, &e, &params, b"message", &[0; 2]),
+                Err("invalid modulus")
+            );
+            assert_eq!(
+                verify_pkcs1(&n, &e, Hash::Sha256, b"message", &[0; 2]),
+                Err("invalid modulus")
+            );
+        }
+    }
+}